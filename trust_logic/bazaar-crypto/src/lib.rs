@@ -0,0 +1,129 @@
+// 🔐 PROJECT BAZAAR | MESSAGE CRYPTO
+// Client-side helper for the on-chain key registry (`publish_key` / `get_encryption_key`).
+// Messages are sealed with an X25519 + XSalsa20-Poly1305 box; the chain only ever sees ciphertext.
+
+use crypto_box::aead::rand_core::CryptoRngCore;
+use crypto_box::aead::{Aead, AeadCore};
+use crypto_box::{Nonce, PublicKey, SalsaBox, SecretKey};
+use std::fmt;
+
+pub const KEY_SIZE: usize = 32;
+pub const NONCE_SIZE: usize = 24;
+pub const TAG_SIZE: usize = 16;
+/// Mirrors `MAX_CIPHERTEXT` in the trust contract.
+pub const MAX_CIPHERTEXT: usize = 1_024;
+pub const MAX_PLAINTEXT: usize = MAX_CIPHERTEXT - TAG_SIZE;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// The keypair does not match the registry entry it claims to be.
+    KeyMismatch,
+    MessageTooLarge,
+    /// Wrong key, wrong nonce or tampered ciphertext.
+    DecryptionFailed,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::KeyMismatch => write!(f, "Keypair does not match registry key"),
+            Error::MessageTooLarge => write!(f, "Message exceeds ciphertext limit"),
+            Error::DecryptionFailed => write!(f, "Decryption failed"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// A registry entry as returned by `get_encryption_key` / `get_key_history`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RegistryKey {
+    pub key: [u8; KEY_SIZE],
+    pub version: u32,
+}
+
+pub struct Keypair {
+    secret: SecretKey,
+}
+
+impl Keypair {
+    pub fn generate(rng: &mut impl CryptoRngCore) -> Self {
+        Keypair { secret: SecretKey::generate(rng) }
+    }
+
+    pub fn from_secret(bytes: [u8; KEY_SIZE]) -> Self {
+        Keypair { secret: SecretKey::from_bytes(bytes) }
+    }
+
+    pub fn secret_bytes(&self) -> [u8; KEY_SIZE] {
+        self.secret.to_bytes()
+    }
+
+    /// The bytes to pass to `publish_key`.
+    pub fn public_key(&self) -> [u8; KEY_SIZE] {
+        *self.secret.public_key().as_bytes()
+    }
+
+    /// Pairs this keypair with the version the registry assigned to it.
+    pub fn registered(&self, key: &RegistryKey) -> Result<RegistryKey, Error> {
+        if key.key != self.public_key() {
+            return Err(Error::KeyMismatch);
+        }
+        Ok(*key)
+    }
+}
+
+/// Arguments for `send_message`, plus the key versions the contract will stamp.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sealed {
+    pub ciphertext: Vec<u8>,
+    pub nonce: [u8; NONCE_SIZE],
+    pub sender_key: u32,
+    pub recipient_key: u32,
+}
+
+/// Seals `plaintext` from `sender` (published as `sender_key`) to the recipient's current registry key.
+pub fn encrypt(
+    rng: &mut impl CryptoRngCore,
+    sender: &Keypair,
+    sender_key: &RegistryKey,
+    recipient_key: &RegistryKey,
+    plaintext: &[u8],
+) -> Result<Sealed, Error> {
+    let sender_key = sender.registered(sender_key)?;
+    if plaintext.len() > MAX_PLAINTEXT {
+        return Err(Error::MessageTooLarge);
+    }
+
+    let salsa = SalsaBox::new(&PublicKey::from(recipient_key.key), &sender.secret);
+    let nonce = SalsaBox::generate_nonce(rng);
+    let ciphertext = salsa.encrypt(&nonce, plaintext).map_err(|_| Error::MessageTooLarge)?;
+
+    Ok(Sealed {
+        ciphertext,
+        nonce: nonce.into(),
+        sender_key: sender_key.version,
+        recipient_key: recipient_key.version,
+    })
+}
+
+/// Opens a stored message. `recipient` must be the keypair for the message's `recipient_key`
+/// version, and `sender_key` the sender's history entry for its `sender_key` version.
+pub fn decrypt(
+    recipient: &Keypair,
+    sender_key: &RegistryKey,
+    ciphertext: &[u8],
+    nonce: &[u8; NONCE_SIZE],
+) -> Result<Vec<u8>, Error> {
+    let salsa = SalsaBox::new(&PublicKey::from(sender_key.key), &recipient.secret);
+    salsa
+        .decrypt(&Nonce::from(*nonce), ciphertext)
+        .map_err(|_| Error::DecryptionFailed)
+}
+
+/// Finds the history entry a message was stamped with.
+pub fn key_for_version(history: &[RegistryKey], version: u32) -> Option<RegistryKey> {
+    history.iter().copied().find(|k| k.version == version)
+}
+
+mod test;
@@ -0,0 +1,72 @@
+#![cfg(test)]
+
+use super::*;
+use crypto_box::aead::OsRng;
+
+fn register(keypair: &Keypair, version: u32) -> RegistryKey {
+    RegistryKey { key: keypair.public_key(), version }
+}
+
+#[test]
+fn round_trip() {
+    let alice = Keypair::generate(&mut OsRng);
+    let bob = Keypair::generate(&mut OsRng);
+    let (alice_key, bob_key) = (register(&alice, 1), register(&bob, 1));
+
+    let sealed = encrypt(&mut OsRng, &alice, &alice_key, &bob_key, b"25kg rice, paid in BZR").unwrap();
+    assert_eq!(sealed.ciphertext.len(), 22 + TAG_SIZE);
+    assert_eq!((sealed.sender_key, sealed.recipient_key), (1, 1));
+
+    let opened = decrypt(&bob, &alice_key, &sealed.ciphertext, &sealed.nonce).unwrap();
+    assert_eq!(opened, b"25kg rice, paid in BZR");
+}
+
+#[test]
+fn wrong_key_fails() {
+    let alice = Keypair::generate(&mut OsRng);
+    let bob = Keypair::generate(&mut OsRng);
+    let eve = Keypair::generate(&mut OsRng);
+    let alice_key = register(&alice, 1);
+
+    let sealed = encrypt(&mut OsRng, &alice, &alice_key, &register(&bob, 1), b"hello").unwrap();
+    assert_eq!(decrypt(&eve, &alice_key, &sealed.ciphertext, &sealed.nonce), Err(Error::DecryptionFailed));
+}
+
+#[test]
+fn tampered_ciphertext_fails() {
+    let alice = Keypair::generate(&mut OsRng);
+    let bob = Keypair::generate(&mut OsRng);
+    let alice_key = register(&alice, 1);
+
+    let mut sealed = encrypt(&mut OsRng, &alice, &alice_key, &register(&bob, 1), b"hello").unwrap();
+    sealed.ciphertext[0] ^= 1;
+    assert_eq!(decrypt(&bob, &alice_key, &sealed.ciphertext, &sealed.nonce), Err(Error::DecryptionFailed));
+}
+
+#[test]
+fn rotated_key_still_opens_old_messages() {
+    let alice = Keypair::generate(&mut OsRng);
+    let bob_v1 = Keypair::generate(&mut OsRng);
+    let bob_v2 = Keypair::generate(&mut OsRng);
+    let alice_key = register(&alice, 1);
+    let bob_history = [register(&bob_v1, 1), register(&bob_v2, 2)];
+
+    let old = encrypt(&mut OsRng, &alice, &alice_key, &bob_history[0], b"before").unwrap();
+    let new = encrypt(&mut OsRng, &alice, &alice_key, &bob_history[1], b"after").unwrap();
+
+    assert_eq!(key_for_version(&bob_history, old.recipient_key).unwrap().key, bob_v1.public_key());
+    assert_eq!(decrypt(&bob_v1, &alice_key, &old.ciphertext, &old.nonce).unwrap(), b"before");
+    assert_eq!(decrypt(&bob_v2, &alice_key, &new.ciphertext, &new.nonce).unwrap(), b"after");
+    assert!(decrypt(&bob_v2, &alice_key, &old.ciphertext, &old.nonce).is_err());
+}
+
+#[test]
+fn rejects_unregistered_sender_and_oversized_message() {
+    let alice = Keypair::generate(&mut OsRng);
+    let bob = Keypair::generate(&mut OsRng);
+    let bob_key = register(&bob, 1);
+
+    assert_eq!(encrypt(&mut OsRng, &alice, &bob_key, &bob_key, b"hi").unwrap_err(), Error::KeyMismatch);
+    let big = vec![0u8; MAX_PLAINTEXT + 1];
+    assert_eq!(encrypt(&mut OsRng, &alice, &register(&alice, 1), &bob_key, &big).unwrap_err(), Error::MessageTooLarge);
+}
@@ -0,0 +1,183 @@
+// 🏛️ PROJECT BAZAAR | SHARED TYPES
+// Everything that crosses a contract boundary: the deployment map, shared records, and the
+// client traits each contract uses to call the others.
+
+#![no_std]
+use soroban_sdk::{contractclient, contracttype, Address, BytesN, Env, Symbol, Vec};
+
+// ============================================================
+// 🔧 SHARED CONSTANTS
+// ============================================================
+
+// Storage TTLs, in ledgers (~5s each).
+pub const LEDGER_SECONDS: u64 = 5;
+pub const DAY_IN_LEDGERS: u32 = 17_280;
+pub const RECORD_TTL: u32 = 90 * DAY_IN_LEDGERS;         // Long-lived per-user records
+pub const RECORD_TTL_THRESHOLD: u32 = 30 * DAY_IN_LEDGERS;
+
+// ============================================================
+// 📦 SHARED DATA STRUCTURES
+// ============================================================
+
+/// Addresses of the core Bazaar contracts. Trust, Vault, Market, Governance, Vesting and Reserve
+/// each receive the full map in their constructor; Pool and Oracle stand alone.
+#[contracttype]
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Deployment {
+    pub trust: Address,
+    pub vault: Address,
+    pub token: Address,
+    pub market: Address,
+    /// Also the treasury: fees are paid to this address in BZR.
+    pub governance: Address,
+}
+
+#[contracttype]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Role {
+    Admin,
+    Arbiter,
+    AcademyIssuer,
+    Instructor,
+}
+
+#[contracttype]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Tier {
+    Basic,
+    Pro,
+    Guild,
+}
+
+/// Price, term and perks of a subscription tier. Also describes the free allowance for non-subscribers.
+#[contracttype]
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct TierConfig {
+    pub price: i128,
+    pub duration: u64,
+    pub listing_limit: u32,
+    pub message_quota: u32,
+}
+
+/// Treasury matching for a campaign. `pool` is moved from the treasury to the Market when the rule is set.
+#[contracttype]
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct MatchingRule {
+    pub ratio_bps: i128,
+    pub cap_per_contributor: i128,
+    pub pool: i128,
+}
+
+/// SEP-40 asset identifier: a Stellar token contract, or an off-chain symbol such as `PHP`.
+#[contracttype]
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Asset {
+    Stellar(Address),
+    Other(Symbol),
+}
+
+/// SEP-40 price record: the asset's price in the oracle's base asset, scaled by its `decimals`.
+#[contracttype]
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct PriceData {
+    pub price: i128,
+    pub timestamp: u64,
+}
+
+/// Who may deposit into or swap on a gated pool, e.g. the Seed Pool: holders of any listed badge
+/// or unrevoked Academy credential on `trust`.
+#[contracttype]
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct PoolGate {
+    pub trust: Address,
+    pub badges: Vec<Symbol>,
+    pub credentials: Vec<Symbol>,
+}
+
+#[contracttype]
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct LegacyVault {
+    pub heir: Address,
+    pub last_heartbeat: u64,
+    pub is_frozen: bool,
+}
+
+// ============================================================
+// 📡 CROSS-CONTRACT CLIENTS
+// ============================================================
+// Only the calls other contracts make are listed here. BZR transfers use the standard
+// SEP-41 `soroban_sdk::token::TokenClient`.
+
+#[contractclient(name = "TrustClient")]
+pub trait TrustInterface {
+    fn get_trust(env: Env, user: Address) -> u32;
+    fn get_past_trust(env: Env, user: Address, ledger: u32) -> u32;
+    fn is_bonded(env: Env, user: Address) -> bool;
+    fn meets_gate(env: Env, feature: Symbol, user: Address) -> bool;
+    fn has_badge(env: Env, user: Address, badge: Symbol) -> bool;
+    fn has_credential(env: Env, holder: Address, course: Symbol) -> bool;
+    fn grant_role(env: Env, role: Role, who: Address);
+    fn revoke_role(env: Env, role: Role, who: Address);
+}
+
+/// BZR extensions beyond SEP-41.
+#[contractclient(name = "BzrClient")]
+pub trait BzrInterface {
+    fn mint(env: Env, minter: Address, to: Address, amount: i128);
+    fn set_minter(env: Env, minter: Address, enabled: bool);
+    fn get_past_votes(env: Env, account: Address, ledger: u32) -> i128;
+    fn total_supply(env: Env) -> i128;
+    fn mintable(env: Env) -> i128;
+}
+
+#[contractclient(name = "MarketClient")]
+pub trait MarketInterface {
+    fn get_perks(env: Env, user: Address) -> TierConfig;
+    fn set_matching(env: Env, name: Symbol, rule: MatchingRule);
+}
+
+#[contractclient(name = "VaultClient")]
+pub trait VaultInterface {
+    fn import_vault(env: Env, user: Address, vault: LegacyVault);
+    fn import_witnesses(env: Env, user: Address, witnesses: Vec<Address>);
+}
+
+#[contractclient(name = "GovernanceClient")]
+pub trait GovernanceInterface {
+    fn get_param(env: Env, name: Symbol) -> Option<i128>;
+}
+
+#[contractclient(name = "PoolClient")]
+pub trait PoolInterface {
+    fn set_gate(env: Env, gate: Option<PoolGate>);
+}
+
+/// The SEP-40 subset the Bazaar reads.
+#[contractclient(name = "OracleClient")]
+pub trait OracleInterface {
+    fn decimals(env: Env) -> u32;
+    fn lastprice(env: Env, asset: Asset) -> Option<PriceData>;
+}
+
+#[contractclient(name = "ReserveClient")]
+pub trait ReserveInterface {
+    fn check_mint(env: Env, new_supply: i128);
+    fn get_headroom(env: Env, supply: i128) -> i128;
+}
+
+#[contractclient(name = "VestingClient")]
+pub trait VestingInterface {
+    fn revoke_grant(env: Env, grant_id: u32);
+}
+
+/// Every Bazaar contract can hand its admin seat over.
+#[contractclient(name = "AdminClient")]
+pub trait AdminInterface {
+    fn set_admin(env: Env, new_admin: Address);
+}
+
+/// Every Bazaar contract exposes an admin-gated `upgrade`.
+#[contractclient(name = "UpgradeClient")]
+pub trait UpgradeInterface {
+    fn upgrade(env: Env, new_wasm_hash: BytesN<32>);
+}
@@ -0,0 +1,653 @@
+// 🏛️ PROJECT BAZAAR | GOVERNANCE
+// The DAO and its treasury: BZR-weighted proposals with a timelock, protocol parameters and
+// multisig wallets. Every BZR fee in the Bazaar is paid to this contract's address.
+//
+// Proposals that act on another contract (roles, upgrades, campaign matching) call that
+// contract's admin entry points, so Governance must hold its admin seat before they can execute.
+
+#![no_std]
+use bazaar_types::{AdminClient, BzrClient, Deployment, MarketClient, MatchingRule, PoolClient, PoolGate, Role, TrustClient, UpgradeClient, VestingClient, RECORD_TTL, RECORD_TTL_THRESHOLD};
+use soroban_sdk::{contract, contractimpl, contracttype, symbol_short, token, Address, BytesN, Env, IntoVal, Map, Symbol, Val, Vec};
+
+// ============================================================
+// 🔧 PROTOCOL CONSTANTS
+// ============================================================
+
+// DAO defaults. Each can be overridden by a `SetParam` proposal under the name in quotes.
+const PROPOSAL_FEE: i128 = 100;         // "prop_fee": BZR paid into the treasury per proposal
+const VOTING_PERIOD: u64 = 604_800;     // "vote_prd": 7 Days
+const QUORUM: i128 = 100;               // "quorum": Minimum BZR weight cast (for + against)
+const PASS_THRESHOLD_BPS: i128 = 5_000; // "threshold": Share of votes "for" required, in basis points (strictly above)
+const TIMELOCK: u64 = 172_800;          // "timelock": 2 Days between queue and execute
+const EXEC_WINDOW: u64 = 1_209_600;     // "exec_win": 14 Days to execute after the timelock before the proposal lapses
+const SENSITIVE_QUORUM: i128 = 1_000;   // "sens_quor": Quorum for upgrades, admin handovers and minter changes
+const SENSITIVE_BPS: i128 = 6_667;      // "sens_thr": Two-thirds "for" on the same
+const VOTER_BOND: i128 = 100;           // "qv_bond": BZR an account must lock to vote in quadratic mode
+const MAX_TRUST_BONUS: u32 = 100;       // Trust-weighted votes scale up to 2x at this score
+const WALLET_TX_TTL: u64 = 604_800;     // Multisig proposals expire after 7 Days
+// Every parameter the DAO can set, so `bump` can keep them alive
+const PARAMS: [&str; 10] = ["prop_fee", "vote_prd", "quorum", "threshold", "timelock", "exec_win", "sens_quor", "sens_thr", "qv_bond", "nick_fee"];
+// Parameters guarding sensitive proposals; changing them needs the sensitive quorum and threshold too
+const GUARD_PARAMS: [Symbol; 4] = [symbol_short!("sens_quor"), symbol_short!("sens_thr"), symbol_short!("timelock"), symbol_short!("exec_win")];
+
+// ============================================================
+// 📦 DATA STRUCTURES
+// ============================================================
+
+/// Typed payload applied by `execute` once a proposal clears its timelock.
+#[contracttype]
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum ProposalAction {
+    SetParam(Symbol, i128),
+    TreasurySpend(Address, i128),
+    /// Applied on the Trust contract.
+    GrantRole(Role, Address),
+    RevokeRole(Role, Address),
+    /// Upgrades any Bazaar contract, this one included.
+    Upgrade(Address, BytesN<32>),
+    /// Hands a Bazaar contract's admin seat, this one's included, to a new holder.
+    SetAdmin(Address, Address),
+    MatchCampaign(Symbol, MatchingRule),
+    /// Sets or lifts a liquidity pool's access gate. The DAO must hold that pool's admin seat.
+    GatePool(Address, PoolGate),
+    OpenPool(Address),
+    /// Revokes the unvested part of a grant on the given vesting contract. The DAO must hold its admin seat.
+    RevokeGrant(Address, u32),
+    /// Approves or removes a BZR minter. The DAO must hold the token's admin seat.
+    SetMinter(Address, bool),
+}
+
+/// Tally rules, fixed when the proposal is created.
+#[contracttype]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum VotingMode {
+    /// One vote per BZR.
+    Token,
+    /// Casting n votes costs n² BZR of voting power; only accounts holding a voter bond may vote.
+    Quadratic,
+    /// BZR weight scaled by (100 + trust_score) / 100, capped at 2x, with trust read at the snapshot ledger.
+    TrustWeighted,
+}
+
+#[contracttype]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ProposalStatus {
+    Active,
+    Defeated,
+    Queued,
+    Executed,
+    Cancelled,
+    /// Queued but not executed within the execution window.
+    Expired,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct Proposal {
+    pub id: u32,
+    pub proposer: Address,
+    pub action: ProposalAction,
+    pub mode: VotingMode,
+    pub snapshot: u32,
+    pub votes_for: i128,
+    pub votes_against: i128,
+    pub turnout: i128,
+    pub start: u64,
+    pub end: u64,
+    pub eta: u64,
+    pub status: ProposalStatus,
+}
+
+/// BZR locked for quadratic voting. Each vote extends the lock to the close of that proposal.
+#[contracttype]
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct VoterBond {
+    pub amount: i128,
+    pub locked_until: u64,
+}
+
+/// Shared M-of-N wallet. BZR and any other SEP-41 tokens are held by this contract and booked per wallet.
+#[contracttype]
+#[derive(Clone)]
+pub struct Wallet {
+    pub owners: Vec<Address>,
+    pub threshold: u32,
+    pub bzr_balance: i128,
+    pub tokens: Map<Address, i128>,
+}
+
+#[contracttype]
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum WalletAction {
+    Transfer(Address, i128),
+    TransferToken(Address, Address, i128),
+    AddOwner(Address),
+    RemoveOwner(Address),
+    SetThreshold(u32),
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct WalletTx {
+    pub wallet: u32,
+    pub action: WalletAction,
+    pub approvals: Vec<Address>,
+    pub expires_at: u64,
+    pub executed: bool,
+}
+
+#[contracttype]
+pub enum DataKey {
+    Admin,
+    Deployment,
+    Param(Symbol),
+    ProposalCount,
+    Proposal(u32),
+    ProposalVote(u32, Address),
+    WalletCount,
+    Wallet(u32),
+    WalletTxCount,
+    WalletTx(u32),
+    /// BZR held here on behalf of wallets and voter bonds, which is not treasury money.
+    WalletReserve,
+    VoterBond(Address),
+}
+
+// ============================================================
+// ⚙️ THE CONTRACT
+// ============================================================
+#[contract]
+pub struct GovernanceContract;
+
+#[contractimpl]
+impl GovernanceContract {
+
+    pub fn __constructor(env: Env, admin: Address, deployment: Deployment) {
+        env.storage().instance().set(&DataKey::Admin, &admin);
+        env.storage().instance().set(&DataKey::Deployment, &deployment);
+    }
+
+    pub fn get_admin(env: Env) -> Address {
+        env.storage().instance().get(&DataKey::Admin).unwrap()
+    }
+
+    pub fn set_admin(env: Env, new_admin: Address) {
+        require_admin(&env);
+        env.storage().instance().set(&DataKey::Admin, &new_admin);
+    }
+
+    /// Admin path; the DAO can do the same through a `ProposalAction::Upgrade` proposal.
+    pub fn upgrade(env: Env, new_wasm_hash: BytesN<32>) {
+        require_admin(&env);
+        env.deployer().update_current_contract_wasm(new_wasm_hash);
+    }
+
+    // --- FEATURE 1: DAO GOVERNANCE ---
+
+    pub fn create_proposal(env: Env, proposer: Address, action: ProposalAction, mode: VotingMode) -> u32 {
+        proposer.require_auth();
+        if !trust(&env).meets_gate(&symbol_short!("proposal"), &proposer) { panic!("Missing required credential"); }
+        let fee = param(&env, symbol_short!("prop_fee"), PROPOSAL_FEE);
+        if fee > 0 { bzr(&env).transfer(&proposer, env.current_contract_address(), &fee); }
+
+        let id: u32 = env.storage().persistent().get(&DataKey::ProposalCount).unwrap_or(0) + 1;
+        let now = env.ledger().timestamp();
+        let proposal = Proposal {
+            id,
+            proposer,
+            action,
+            mode,
+            snapshot: env.ledger().sequence(),
+            votes_for: 0,
+            votes_against: 0,
+            turnout: 0,
+            start: now,
+            end: now + param(&env, symbol_short!("vote_prd"), VOTING_PERIOD as i128) as u64,
+            eta: 0,
+            status: ProposalStatus::Active,
+        };
+        store(&env, &DataKey::Proposal(id), &proposal);
+        store(&env, &DataKey::ProposalCount, &id);
+        id
+    }
+
+    /// Weight is the voter's delegated BZR as of the proposal's creation ledger, so BZR
+    /// moved after creation cannot be voted twice. Voting opens on the following ledger.
+    pub fn vote(env: Env, voter: Address, id: u32, support: bool) {
+        voter.require_auth();
+        let key = DataKey::Proposal(id);
+        let mut proposal: Proposal = env.storage().persistent().get(&key).expect("Proposal not found");
+        if proposal.status != ProposalStatus::Active || env.ledger().timestamp() > proposal.end {
+            panic!("Voting closed");
+        }
+        if env.ledger().sequence() <= proposal.snapshot { panic!("Voting not started"); }
+
+        let voted_key = DataKey::ProposalVote(id, voter.clone());
+        if env.storage().persistent().has(&voted_key) { panic!("Already voted"); }
+        let power = BzrClient::new(&env, &deployment(&env).token).get_past_votes(&voter, &proposal.snapshot);
+        if power <= 0 { panic!("No voting power"); }
+
+        let weight = match proposal.mode {
+            VotingMode::Token => power,
+            VotingMode::Quadratic => {
+                // A bond per account makes splitting BZR across sockpuppets cost real BZR, and
+                // locking it until the vote closes stops one bond being passed between them.
+                let mut bond = voter_bond(&env, &voter);
+                if bond.amount < param(&env, symbol_short!("qv_bond"), VOTER_BOND) { panic!("Quadratic voting requires a bond"); }
+                bond.locked_until = bond.locked_until.max(proposal.end);
+                store(&env, &DataKey::VoterBond(voter.clone()), &bond);
+                isqrt(power)
+            }
+            VotingMode::TrustWeighted => {
+                let score = trust(&env).get_past_trust(&voter, &proposal.snapshot).min(MAX_TRUST_BONUS);
+                power * (100 + score as i128) / 100
+            }
+        };
+        if support { proposal.votes_for += weight; } else { proposal.votes_against += weight; }
+        proposal.turnout += power;
+        store(&env, &voted_key, &support);
+        store(&env, &key, &proposal);
+    }
+
+    /// Locks BZR as the caller's voter bond, which quadratic votes require.
+    pub fn bond_voter(env: Env, voter: Address, amount: i128) {
+        voter.require_auth();
+        if amount <= 0 { panic!("Invalid amount"); }
+        bzr(&env).transfer(&voter, env.current_contract_address(), &amount);
+        set_reserve(&env, reserve(&env) + amount);
+        let mut bond = voter_bond(&env, &voter);
+        bond.amount += amount;
+        store(&env, &DataKey::VoterBond(voter), &bond);
+    }
+
+    /// Returns the whole bond once every quadratic vote it backed has closed.
+    pub fn unbond_voter(env: Env, voter: Address) -> i128 {
+        voter.require_auth();
+        let bond = voter_bond(&env, &voter);
+        if bond.amount == 0 { panic!("No voter bond"); }
+        if env.ledger().timestamp() <= bond.locked_until { panic!("Bond locked by an open vote"); }
+        env.storage().persistent().remove(&DataKey::VoterBond(voter.clone()));
+        set_reserve(&env, reserve(&env) - bond.amount);
+        bzr(&env).transfer(&env.current_contract_address(), &voter, &bond.amount);
+        bond.amount
+    }
+
+    pub fn get_voter_bond(env: Env, voter: Address) -> VoterBond {
+        voter_bond(&env, &voter)
+    }
+
+    pub fn get_proposal(env: Env, id: u32) -> Proposal {
+        env.storage().persistent().get(&DataKey::Proposal(id)).expect("Proposal not found")
+    }
+
+    pub fn get_proposal_stats(env: Env, id: u32) -> (i128, i128) {
+        let proposal = Self::get_proposal(env, id);
+        (proposal.votes_for, proposal.votes_against)
+    }
+
+    /// Permissionless once voting ends. Quorum is measured in BZR turnout, the pass threshold
+    /// against the tally under the proposal's voting mode. Passing proposals enter the timelock.
+    /// Upgrades, admin handovers and changes to their guard parameters must clear a higher quorum
+    /// and a two-thirds majority.
+    pub fn queue(env: Env, id: u32) {
+        let key = DataKey::Proposal(id);
+        let mut proposal: Proposal = env.storage().persistent().get(&key).expect("Proposal not found");
+        if proposal.status != ProposalStatus::Active { panic!("Proposal not active"); }
+        let now = env.ledger().timestamp();
+        if now <= proposal.end { panic!("Voting still open"); }
+
+        let cast = proposal.votes_for + proposal.votes_against;
+        let (quorum, threshold) = if is_sensitive(&proposal.action) {
+            (param(&env, symbol_short!("sens_quor"), SENSITIVE_QUORUM), param(&env, symbol_short!("sens_thr"), SENSITIVE_BPS))
+        } else {
+            (param(&env, symbol_short!("quorum"), QUORUM), param(&env, symbol_short!("threshold"), PASS_THRESHOLD_BPS))
+        };
+        if proposal.turnout >= quorum && proposal.votes_for * 10_000 > threshold * cast {
+            proposal.status = ProposalStatus::Queued;
+            proposal.eta = now + param(&env, symbol_short!("timelock"), TIMELOCK as i128) as u64;
+        } else {
+            proposal.status = ProposalStatus::Defeated;
+        }
+        store(&env, &key, &proposal);
+    }
+
+    /// Permissionless once the timelock expires. A proposal left past its execution window
+    /// lapses instead of running.
+    pub fn execute(env: Env, id: u32) {
+        let key = DataKey::Proposal(id);
+        let mut proposal: Proposal = env.storage().persistent().get(&key).expect("Proposal not found");
+        if proposal.status != ProposalStatus::Queued { panic!("Proposal not queued"); }
+        let now = env.ledger().timestamp();
+        if now < proposal.eta { panic!("Timelock active"); }
+        if now > proposal.eta + param(&env, symbol_short!("exec_win"), EXEC_WINDOW as i128) as u64 {
+            proposal.status = ProposalStatus::Expired;
+            store(&env, &key, &proposal);
+            return;
+        }
+
+        proposal.status = ProposalStatus::Executed;
+        store(&env, &key, &proposal);
+
+        let this = env.current_contract_address();
+        match proposal.action {
+            ProposalAction::SetParam(name, value) => {
+                if value < 0 { panic!("Invalid parameter"); }
+                store(&env, &DataKey::Param(name), &value);
+            }
+            ProposalAction::TreasurySpend(to, amount) => {
+                check_treasury(&env, amount);
+                bzr(&env).transfer(&this, &to, &amount);
+            }
+            ProposalAction::GrantRole(role, who) => trust(&env).grant_role(&role, &who),
+            ProposalAction::RevokeRole(role, who) => trust(&env).revoke_role(&role, &who),
+            ProposalAction::Upgrade(contract, wasm_hash) => {
+                if contract == this {
+                    env.deployer().update_current_contract_wasm(wasm_hash);
+                } else {
+                    UpgradeClient::new(&env, &contract).upgrade(&wasm_hash);
+                }
+            }
+            ProposalAction::SetAdmin(contract, new_admin) => {
+                if contract == this {
+                    env.storage().instance().set(&DataKey::Admin, &new_admin);
+                } else {
+                    AdminClient::new(&env, &contract).set_admin(&new_admin);
+                }
+            }
+            ProposalAction::MatchCampaign(name, rule) => {
+                check_treasury(&env, rule.pool);
+                let market = deployment(&env).market;
+                bzr(&env).transfer(&this, &market, &rule.pool);
+                MarketClient::new(&env, &market).set_matching(&name, &rule);
+            }
+            ProposalAction::GatePool(pool, gate) => PoolClient::new(&env, &pool).set_gate(&Some(gate)),
+            ProposalAction::OpenPool(pool) => PoolClient::new(&env, &pool).set_gate(&None),
+            ProposalAction::RevokeGrant(vesting, id) => VestingClient::new(&env, &vesting).revoke_grant(&id),
+            ProposalAction::SetMinter(minter, enabled) => BzrClient::new(&env, &deployment(&env).token).set_minter(&minter, &enabled),
+        }
+    }
+
+    /// The proposer may withdraw, and the admin veto, any proposal that has not executed yet.
+    /// The proposal fee stays in the treasury.
+    pub fn cancel_proposal(env: Env, caller: Address, id: u32) {
+        caller.require_auth();
+        let key = DataKey::Proposal(id);
+        let mut proposal: Proposal = env.storage().persistent().get(&key).expect("Proposal not found");
+        if proposal.status != ProposalStatus::Active && proposal.status != ProposalStatus::Queued {
+            panic!("Proposal not cancellable");
+        }
+        if caller != proposal.proposer && caller != Self::get_admin(env.clone()) { panic!("Not allowed to cancel"); }
+        proposal.status = ProposalStatus::Cancelled;
+        store(&env, &key, &proposal);
+    }
+
+    pub fn get_param(env: Env, name: Symbol) -> Option<i128> {
+        env.storage().persistent().get(&DataKey::Param(name))
+    }
+
+    /// BZR this contract holds, less what is booked to multisig wallets.
+    pub fn get_treasury(env: Env) -> i128 {
+        treasury(&env)
+    }
+
+    // --- FEATURE 2: MULTISIG WALLETS ---
+
+    pub fn create_wallet(env: Env, creator: Address, owners: Vec<Address>, threshold: u32) -> u32 {
+        creator.require_auth();
+        if !owners.contains(creator) { panic!("Creator must be an owner"); }
+        for (i, owner) in owners.iter().enumerate() {
+            if owners.first_index_of(owner) != Some(i as u32) { panic!("Duplicate owner"); }
+        }
+        if threshold == 0 || threshold > owners.len() { panic!("Invalid threshold"); }
+
+        let id: u32 = env.storage().persistent().get(&DataKey::WalletCount).unwrap_or(0) + 1;
+        let wallet = Wallet { owners, threshold, bzr_balance: 0, tokens: Map::new(&env) };
+        store(&env, &DataKey::Wallet(id), &wallet);
+        store(&env, &DataKey::WalletCount, &id);
+        id
+    }
+
+    pub fn get_wallet(env: Env, wallet_id: u32) -> Wallet {
+        env.storage().persistent().get(&DataKey::Wallet(wallet_id)).expect("Wallet not found")
+    }
+
+    pub fn deposit_wallet(env: Env, from: Address, wallet_id: u32, amount: i128) {
+        from.require_auth();
+        if amount <= 0 { panic!("Invalid amount"); }
+        let mut wallet = Self::get_wallet(env.clone(), wallet_id);
+        bzr(&env).transfer(&from, env.current_contract_address(), &amount);
+        wallet.bzr_balance += amount;
+        set_reserve(&env, reserve(&env) + amount);
+        store(&env, &DataKey::Wallet(wallet_id), &wallet);
+    }
+
+    /// Pulls SEP-41 tokens into the contract and books them to the wallet. BZR is booked
+    /// as the wallet's BZR balance so it stays out of the treasury.
+    pub fn deposit_wallet_token(env: Env, from: Address, wallet_id: u32, token: Address, amount: i128) {
+        if token == deployment(&env).token {
+            return Self::deposit_wallet(env, from, wallet_id, amount);
+        }
+        from.require_auth();
+        if amount <= 0 { panic!("Invalid amount"); }
+        let mut wallet = Self::get_wallet(env.clone(), wallet_id);
+        token::Client::new(&env, &token).transfer(&from, env.current_contract_address(), &amount);
+        let held = wallet.tokens.get(token.clone()).unwrap_or(0);
+        wallet.tokens.set(token, held + amount);
+        store(&env, &DataKey::Wallet(wallet_id), &wallet);
+    }
+
+    /// Shorthand for proposing a BZR transfer out of the wallet.
+    pub fn propose_tx(env: Env, proposer: Address, wallet_id: u32, to: Address, amount: i128) -> u32 {
+        Self::propose_wallet_action(env, proposer, wallet_id, WalletAction::Transfer(to, amount))
+    }
+
+    /// The proposer's approval is counted immediately. Owner and threshold changes go through
+    /// the same M-of-N flow as transfers.
+    pub fn propose_wallet_action(env: Env, proposer: Address, wallet_id: u32, action: WalletAction) -> u32 {
+        proposer.require_auth();
+        let wallet = Self::get_wallet(env.clone(), wallet_id);
+        if !wallet.owners.contains(proposer.clone()) { panic!("Not an owner"); }
+
+        let id: u32 = env.storage().persistent().get(&DataKey::WalletTxCount).unwrap_or(0) + 1;
+        let mut tx = WalletTx {
+            wallet: wallet_id,
+            action,
+            approvals: Vec::new(&env),
+            expires_at: env.ledger().timestamp() + WALLET_TX_TTL,
+            executed: false,
+        };
+        tx.approvals.push_back(proposer);
+        store(&env, &DataKey::WalletTxCount, &id);
+        try_execute_wallet_tx(&env, id, tx, wallet);
+        id
+    }
+
+    pub fn approve_tx(env: Env, owner: Address, tx_id: u32) {
+        owner.require_auth();
+        let mut tx = Self::get_wallet_tx(env.clone(), tx_id);
+        if tx.executed { panic!("Already executed"); }
+        if env.ledger().timestamp() > tx.expires_at { panic!("Proposal expired"); }
+        let wallet = Self::get_wallet(env.clone(), tx.wallet);
+        if !wallet.owners.contains(owner.clone()) { panic!("Not an owner"); }
+        if tx.approvals.contains(owner.clone()) { panic!("Already approved"); }
+
+        tx.approvals.push_back(owner);
+        try_execute_wallet_tx(&env, tx_id, tx, wallet);
+    }
+
+    /// Permissionless. Runs a pending tx whose approvals already meet the wallet's current
+    /// threshold, e.g. after the threshold was lowered.
+    pub fn execute_tx(env: Env, tx_id: u32) {
+        let tx = Self::get_wallet_tx(env.clone(), tx_id);
+        if tx.executed { panic!("Already executed"); }
+        if env.ledger().timestamp() > tx.expires_at { panic!("Proposal expired"); }
+        let wallet = Self::get_wallet(env.clone(), tx.wallet);
+        if !try_execute_wallet_tx(&env, tx_id, tx, wallet) { panic!("Not enough approvals"); }
+    }
+
+    pub fn revoke_approval(env: Env, owner: Address, tx_id: u32) {
+        owner.require_auth();
+        let mut tx = Self::get_wallet_tx(env.clone(), tx_id);
+        if tx.executed { panic!("Already executed"); }
+        let i = tx.approvals.first_index_of(owner).expect("Not approved");
+        tx.approvals.remove(i);
+        store(&env, &DataKey::WalletTx(tx_id), &tx);
+    }
+
+    pub fn get_wallet_tx(env: Env, tx_id: u32) -> WalletTx {
+        env.storage().persistent().get(&DataKey::WalletTx(tx_id)).expect("Proposal not found")
+    }
+
+    // --- FEATURE 3: STORAGE UPKEEP ---
+
+    /// Permissionless: tops up the TTL of the DAO's parameters and counters, `voter`'s bond,
+    /// plus the contract itself. Proposals and wallets have their own bumps.
+    pub fn bump(env: Env, voter: Address) {
+        env.storage().instance().extend_ttl(RECORD_TTL_THRESHOLD, RECORD_TTL);
+        for key in [
+            DataKey::ProposalCount,
+            DataKey::WalletCount,
+            DataKey::WalletTxCount,
+            DataKey::WalletReserve,
+            DataKey::VoterBond(voter),
+        ] {
+            extend_record(&env, &key);
+        }
+        for name in PARAMS {
+            extend_record(&env, &DataKey::Param(Symbol::new(&env, name)));
+        }
+    }
+
+    pub fn bump_proposal(env: Env, id: u32) {
+        extend_record(&env, &DataKey::Proposal(id));
+    }
+
+    /// Tops up a wallet, which holds funds and may sit idle far longer than a proposal.
+    pub fn bump_wallet(env: Env, wallet_id: u32) {
+        extend_record(&env, &DataKey::Wallet(wallet_id));
+    }
+}
+
+// ============================================================
+// 🛠️ INTERNAL HELPERS
+// ============================================================
+
+fn require_admin(env: &Env) -> Address {
+    let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+    admin.require_auth();
+    admin
+}
+
+fn deployment(env: &Env) -> Deployment {
+    env.storage().instance().get(&DataKey::Deployment).unwrap()
+}
+
+fn bzr(env: &Env) -> token::Client<'_> {
+    token::Client::new(env, &deployment(env).token)
+}
+
+fn trust(env: &Env) -> TrustClient<'_> {
+    TrustClient::new(env, &deployment(env).trust)
+}
+
+/// Writes a persistent record and tops up its TTL.
+fn store<V: IntoVal<Env, Val>>(env: &Env, key: &DataKey, value: &V) {
+    env.storage().persistent().set(key, value);
+    extend_record(env, key);
+}
+
+fn extend_record(env: &Env, key: &DataKey) {
+    if !env.storage().persistent().has(key) { return; }
+    env.storage().persistent().extend_ttl(key, RECORD_TTL_THRESHOLD, RECORD_TTL);
+}
+
+/// Governance parameter with a compiled-in fallback.
+fn param(env: &Env, name: Symbol, default: i128) -> i128 {
+    env.storage().persistent().get(&DataKey::Param(name)).unwrap_or(default)
+}
+
+fn reserve(env: &Env) -> i128 {
+    env.storage().persistent().get(&DataKey::WalletReserve).unwrap_or(0)
+}
+
+fn set_reserve(env: &Env, amount: i128) {
+    store(env, &DataKey::WalletReserve, &amount);
+}
+
+fn voter_bond(env: &Env, voter: &Address) -> VoterBond {
+    env.storage().persistent().get(&DataKey::VoterBond(voter.clone())).unwrap_or(VoterBond { amount: 0, locked_until: 0 })
+}
+
+fn treasury(env: &Env) -> i128 {
+    bzr(env).balance(&env.current_contract_address()) - reserve(env)
+}
+
+fn check_treasury(env: &Env, amount: i128) {
+    if amount <= 0 || treasury(env) < amount { panic!("Insufficient treasury"); }
+}
+
+/// Actions that can take over a contract: upgrades and admin handovers, including Trust's admin role,
+/// plus any parameter change that would lower the bar or shorten the delay on those.
+fn is_sensitive(action: &ProposalAction) -> bool {
+    match action {
+        ProposalAction::SetParam(name, _) => GUARD_PARAMS.contains(name),
+        _ => matches!(
+            action,
+            ProposalAction::Upgrade(..) | ProposalAction::SetAdmin(..) | ProposalAction::SetMinter(..) | ProposalAction::GrantRole(Role::Admin, _)
+        ),
+    }
+}
+
+/// Integer square root (floor) used for quadratic tallies.
+fn isqrt(n: i128) -> i128 {
+    if n < 2 { return n; }
+    let mut x = n;
+    let mut y = (x + 1) / 2;
+    while y < x {
+        x = y;
+        y = (x + n / x) / 2;
+    }
+    x
+}
+
+/// Executes once approvals from current owners reach the threshold, otherwise just stores the tx.
+/// Returns whether it executed.
+fn try_execute_wallet_tx(env: &Env, tx_id: u32, mut tx: WalletTx, mut wallet: Wallet) -> bool {
+    let approvals = tx.approvals.iter().filter(|a| wallet.owners.contains(a.clone())).count() as u32;
+    let ready = approvals >= wallet.threshold;
+    if ready {
+        match tx.action.clone() {
+            WalletAction::Transfer(to, amount) => {
+                if amount <= 0 || wallet.bzr_balance < amount { panic!("Insufficient wallet balance"); }
+                wallet.bzr_balance -= amount;
+                set_reserve(env, reserve(env) - amount);
+                bzr(env).transfer(&env.current_contract_address(), &to, &amount);
+            }
+            WalletAction::TransferToken(token, to, amount) => {
+                let held = wallet.tokens.get(token.clone()).unwrap_or(0);
+                if amount <= 0 || held < amount { panic!("Insufficient wallet balance"); }
+                wallet.tokens.set(token.clone(), held - amount);
+                token::Client::new(env, &token).transfer(&env.current_contract_address(), &to, &amount);
+            }
+            WalletAction::AddOwner(owner) => {
+                if wallet.owners.contains(owner.clone()) { panic!("Duplicate owner"); }
+                wallet.owners.push_back(owner);
+            }
+            WalletAction::RemoveOwner(owner) => {
+                let i = wallet.owners.first_index_of(owner).expect("Not an owner");
+                wallet.owners.remove(i);
+                if wallet.threshold > wallet.owners.len() { panic!("Invalid threshold"); }
+            }
+            WalletAction::SetThreshold(threshold) => {
+                if threshold == 0 || threshold > wallet.owners.len() { panic!("Invalid threshold"); }
+                wallet.threshold = threshold;
+            }
+        }
+        tx.executed = true;
+        store(env, &DataKey::Wallet(tx.wallet), &wallet);
+    }
+    store(env, &DataKey::WalletTx(tx_id), &tx);
+    ready
+}
+
+mod test;
@@ -0,0 +1,792 @@
+#![cfg(test)]
+
+use super::*;
+use bazaar_market::{MarketContract, MarketContractClient};
+use bazaar_pool::{PoolConfig, PoolContract, PoolContractClient};
+use bazaar_token::{BzrToken, BzrTokenClient};
+use bazaar_trust::{TrustContract, TrustContractClient};
+use bazaar_vesting::{Schedule, VestingContract, VestingContractClient};
+use soroban_sdk::{
+    symbol_short,
+    testutils::{storage::Persistent as _, Address as _, Ledger},
+    vec, Address, BytesN, Env,
+};
+
+struct Bazaar {
+    admin: Address,
+    gov: GovernanceContractClient<'static>,
+    token: BzrTokenClient<'static>,
+    trust: TrustContractClient<'static>,
+    market: MarketContractClient<'static>,
+}
+
+impl Bazaar {
+    fn mint(&self, to: &Address, amount: &i128) {
+        self.token.mint(&self.admin, to, amount);
+    }
+
+    /// Votes `id` through with `voter`'s weight and runs it past the timelock.
+    fn pass(&self, env: &Env, voter: &Address, id: u32) {
+        next_ledger(env);
+        self.gov.vote(voter, &id, &true);
+        env.ledger().with_mut(|li| { li.timestamp += 604_801; });
+        self.gov.queue(&id);
+        env.ledger().with_mut(|li| { li.timestamp += 172_800; });
+        self.gov.execute(&id);
+    }
+}
+
+fn setup(env: &Env) -> Bazaar {
+    let admin = Address::generate(env);
+    let deployment = Deployment {
+        trust: Address::generate(env),
+        vault: Address::generate(env),
+        token: Address::generate(env),
+        market: Address::generate(env),
+        governance: Address::generate(env),
+    };
+    env.register_at(&deployment.governance, GovernanceContract, (&admin, &deployment));
+    env.register_at(&deployment.token, BzrToken, (&admin,));
+    env.register_at(&deployment.trust, TrustContract, (&admin, &deployment));
+    env.register_at(&deployment.market, MarketContract, (&admin, &deployment));
+    let bz = Bazaar {
+        gov: GovernanceContractClient::new(env, &deployment.governance),
+        token: BzrTokenClient::new(env, &deployment.token),
+        trust: TrustContractClient::new(env, &deployment.trust),
+        market: MarketContractClient::new(env, &deployment.market),
+        admin,
+    };
+    // Role proposals act on Trust, so the DAO holds its admin seat
+    bz.trust.grant_role(&Role::Admin, &deployment.governance);
+    bz
+}
+
+fn next_ledger(env: &Env) {
+    env.ledger().with_mut(|li| { li.sequence_number += 1; });
+}
+
+// --- DAO GOVERNANCE ---
+
+#[test]
+fn test_create_proposal() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let bz = setup(&env);
+    let user = Address::generate(&env);
+    bz.mint(&user, &100);
+
+    let id = bz.gov.create_proposal(&user, &ProposalAction::SetParam(symbol_short!("quorum"), 10), &VotingMode::Token);
+    assert_eq!(id, 1);
+    assert_eq!(bz.gov.get_proposal(&id).proposer, user);
+    assert_eq!(bz.token.balance(&user), 0);
+}
+
+#[test]
+fn test_voting() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let bz = setup(&env);
+    let proposer = Address::generate(&env);
+    let user = Address::generate(&env);
+    bz.token.set_minter(&bz.trust.address, &true);
+    bz.mint(&proposer, &100);
+
+    // Earn 10 BZR by vouching for two bonded merchants
+    bz.trust.stake(&user, &None);
+    for _ in 0..2 {
+        let merchant = Address::generate(&env);
+        bz.trust.stake(&merchant, &None);
+        bz.trust.vouch(&user, &merchant);
+    }
+    next_ledger(&env);
+
+    let id = bz.gov.create_proposal(&proposer, &ProposalAction::SetParam(symbol_short!("quorum"), 10), &VotingMode::Token);
+    next_ledger(&env);
+    bz.gov.vote(&user, &id, &true); // Vote Yes
+    assert_eq!(bz.gov.get_proposal_stats(&id), (10, 0));
+}
+
+#[test]
+fn test_proposal_executes_treasury_spend() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let bz = setup(&env);
+    let proposer = Address::generate(&env);
+    let voter = Address::generate(&env);
+    let grantee = Address::generate(&env);
+
+    bz.mint(&proposer, &100);
+    bz.mint(&voter, &150);
+
+    let id = bz.gov.create_proposal(&proposer, &ProposalAction::TreasurySpend(grantee.clone(), 60), &VotingMode::Token);
+    assert_eq!(id, 1);
+    assert_eq!(bz.gov.get_treasury(), 100); // Proposal fee
+
+    next_ledger(&env);
+    bz.gov.vote(&voter, &id, &true);
+    assert_eq!(bz.gov.get_proposal_stats(&id), (150, 0));
+
+    env.ledger().with_mut(|li| { li.timestamp += 604_801; });
+    bz.gov.queue(&id);
+    assert_eq!(bz.gov.get_proposal(&id).status, ProposalStatus::Queued);
+
+    env.ledger().with_mut(|li| { li.timestamp += 172_800; });
+    bz.gov.execute(&id);
+
+    assert_eq!(bz.token.balance(&grantee), 60);
+    assert_eq!(bz.gov.get_treasury(), 40);
+    assert_eq!(bz.gov.get_proposal(&id).status, ProposalStatus::Executed);
+}
+
+#[test]
+fn test_proposal_below_quorum_is_defeated() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let bz = setup(&env);
+    let proposer = Address::generate(&env);
+    let voter = Address::generate(&env);
+
+    bz.mint(&proposer, &100);
+    bz.mint(&voter, &99);
+
+    let id = bz.gov.create_proposal(&proposer, &ProposalAction::SetParam(symbol_short!("quorum"), 10), &VotingMode::Token);
+    next_ledger(&env);
+    bz.gov.vote(&voter, &id, &true);
+
+    env.ledger().with_mut(|li| { li.timestamp += 604_801; });
+    bz.gov.queue(&id);
+    assert_eq!(bz.gov.get_proposal(&id).status, ProposalStatus::Defeated);
+    assert_eq!(bz.gov.get_param(&symbol_short!("quorum")), None);
+}
+
+#[test]
+fn test_param_and_role_proposals_change_behavior() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let bz = setup(&env);
+    let proposer = Address::generate(&env);
+    let arbiter = Address::generate(&env);
+
+    bz.mint(&proposer, &300);
+
+    let fee = bz.gov.create_proposal(&proposer, &ProposalAction::SetParam(symbol_short!("prop_fee"), 10), &VotingMode::Token);
+    let role = bz.gov.create_proposal(&proposer, &ProposalAction::GrantRole(Role::Arbiter, arbiter.clone()), &VotingMode::Token);
+    next_ledger(&env);
+    bz.gov.vote(&proposer, &fee, &true);
+    bz.gov.vote(&proposer, &role, &true);
+
+    env.ledger().with_mut(|li| { li.timestamp += 604_801 + 172_800; });
+    bz.gov.queue(&fee);
+    bz.gov.queue(&role);
+    env.ledger().with_mut(|li| { li.timestamp += 172_800; });
+    bz.gov.execute(&fee);
+    bz.gov.execute(&role);
+
+    assert!(bz.trust.has_role(&Role::Arbiter, &arbiter));
+    bz.gov.create_proposal(&proposer, &ProposalAction::SetParam(symbol_short!("quorum"), 1), &VotingMode::Token);
+    assert_eq!(bz.token.balance(&proposer), 90); // Third proposal only cost 10
+
+    // New disputes are routed to the DAO-appointed arbiter
+    let accuser = Address::generate(&env);
+    let target = Address::generate(&env);
+    bz.trust.stake(&target, &None);
+    bz.trust.raise_dispute(&accuser, &target);
+    assert_eq!(bz.trust.get_dispute(&target).unwrap().arbiter, arbiter);
+}
+
+#[test]
+fn test_revoke_role_proposal() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let bz = setup(&env);
+    let proposer = Address::generate(&env);
+    let instructor = Address::generate(&env);
+
+    bz.mint(&proposer, &200);
+    bz.trust.grant_role(&Role::Instructor, &instructor);
+
+    let id = bz.gov.create_proposal(&proposer, &ProposalAction::RevokeRole(Role::Instructor, instructor.clone()), &VotingMode::Token);
+    bz.pass(&env, &proposer, id);
+    assert!(!bz.trust.has_role(&Role::Instructor, &instructor));
+}
+
+#[test]
+fn test_gate_pool_proposal() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let bz = setup(&env);
+    let proposer = Address::generate(&env);
+    let config = PoolConfig { fee_bps: 30, min_swap_a: 1, min_swap_b: 1, guardian_cut_bps: 10_000, min_guardian_bond: 0, max_attestation_age: 604_800 };
+    let pool_id = env.register(PoolContract, (&bz.gov.address, &bz.token.address, Address::generate(&env), config));
+    let pool = PoolContractClient::new(&env, &pool_id);
+
+    bz.mint(&proposer, &300);
+    let gate = PoolGate { trust: bz.trust.address.clone(), badges: vec![&env, symbol_short!("uptime01")], credentials: vec![&env] };
+    let id = bz.gov.create_proposal(&proposer, &ProposalAction::GatePool(pool.address.clone(), gate.clone()), &VotingMode::Token);
+    bz.pass(&env, &proposer, id);
+    assert_eq!(pool.get_gate(), Some(gate));
+    assert!(!pool.can_access(&proposer));
+
+    let id = bz.gov.create_proposal(&proposer, &ProposalAction::OpenPool(pool.address.clone()), &VotingMode::Token);
+    bz.pass(&env, &proposer, id);
+    assert!(pool.can_access(&proposer));
+}
+
+#[test]
+fn test_revoke_grant_proposal() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let bz = setup(&env);
+    let founder = Address::generate(&env);
+    let proposer = Address::generate(&env);
+    let vesting = VestingContractClient::new(&env, &env.register(VestingContract, (&bz.gov.address, bz.trust.get_deployment())));
+
+    bz.mint(&founder, &1_000);
+    let schedule = Schedule { start: env.ledger().timestamp(), cliff: 0, duration: 1_000_000 };
+    let grant = vesting.create_grant(&founder, &founder, &1_000, &schedule, &true);
+
+    bz.mint(&proposer, &200);
+    let id = bz.gov.create_proposal(&proposer, &ProposalAction::RevokeGrant(vesting.address.clone(), grant), &VotingMode::Token);
+    let treasury = bz.gov.get_treasury();
+    bz.pass(&env, &proposer, id);
+
+    // Voting and the timelock took 777,601 seconds of the 1,000,000
+    let status = vesting.get_vesting(&grant);
+    assert_eq!((status.vested, status.unvested), (777, 0));
+    assert_eq!(bz.gov.get_treasury(), treasury + 223);
+}
+
+#[test]
+fn test_token_authority_moves_to_governance() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let bz = setup(&env);
+    let founder = bz.admin.clone();
+    let holder = Address::generate(&env);
+    let vesting = VestingContractClient::new(&env, &env.register(VestingContract, (&bz.gov.address, bz.trust.get_deployment())));
+
+    // Genesis: the founder allocation is locked in a grant, then the admin seat moves to the DAO
+    bz.mint(&founder, &1_000);
+    let schedule = Schedule { start: env.ledger().timestamp(), cliff: 31_536_000, duration: 94_608_000 };
+    vesting.create_grant(&founder, &founder, &1_000, &schedule, &true);
+    bz.mint(&holder, &1_200);
+    bz.token.set_admin(&bz.gov.address);
+    assert!(bz.token.try_mint(&founder, &founder, &1).is_err());
+
+    // Only the DAO can add minters now
+    let id = bz.gov.create_proposal(&holder, &ProposalAction::SetMinter(bz.trust.address.clone(), true), &VotingMode::Token);
+    bz.pass(&env, &holder, id);
+    assert!(bz.token.is_minter(&bz.trust.address));
+}
+
+#[test]
+fn test_proposal_credential_gate() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let bz = setup(&env);
+    let instructor = Address::generate(&env);
+    let proposer = Address::generate(&env);
+    let course = symbol_short!("civics01");
+
+    bz.mint(&proposer, &100);
+    bz.trust.grant_role(&Role::Instructor, &instructor);
+    bz.trust.set_credential_gate(&symbol_short!("proposal"), &Some(course.clone()));
+
+    let action = ProposalAction::SetParam(symbol_short!("quorum"), 10);
+    assert!(bz.gov.try_create_proposal(&proposer, &action, &VotingMode::Token).is_err());
+    bz.trust.issue_credential(&instructor, &proposer, &course, &BytesN::from_array(&env, &[2; 32]));
+    bz.gov.create_proposal(&proposer, &action, &VotingMode::Token);
+}
+
+#[test]
+#[should_panic(expected = "Insufficient treasury")]
+fn test_spend_beyond_treasury_panics() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let bz = setup(&env);
+    let proposer = Address::generate(&env);
+
+    bz.mint(&proposer, &200);
+    let id = bz.gov.create_proposal(&proposer, &ProposalAction::TreasurySpend(proposer.clone(), 101), &VotingMode::Token);
+    bz.pass(&env, &proposer, id);
+}
+
+#[test]
+#[should_panic(expected = "Timelock active")]
+fn test_execute_before_timelock_panics() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let bz = setup(&env);
+    let proposer = Address::generate(&env);
+
+    bz.mint(&proposer, &200);
+
+    let id = bz.gov.create_proposal(&proposer, &ProposalAction::SetParam(symbol_short!("quorum"), 10), &VotingMode::Token);
+    next_ledger(&env);
+    bz.gov.vote(&proposer, &id, &true);
+    env.ledger().with_mut(|li| { li.timestamp += 604_801; });
+    bz.gov.queue(&id);
+    bz.gov.execute(&id);
+}
+
+#[test]
+#[should_panic(expected = "Already voted")]
+fn test_double_vote_panics() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let bz = setup(&env);
+    let proposer = Address::generate(&env);
+
+    bz.mint(&proposer, &200);
+
+    let id = bz.gov.create_proposal(&proposer, &ProposalAction::SetParam(symbol_short!("quorum"), 10), &VotingMode::Token);
+    next_ledger(&env);
+    bz.gov.vote(&proposer, &id, &true);
+    bz.gov.vote(&proposer, &id, &false);
+}
+
+#[test]
+fn test_moved_bzr_cannot_vote_twice() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let bz = setup(&env);
+    let proposer = Address::generate(&env);
+    let holder = Address::generate(&env);
+    let sybil = Address::generate(&env);
+
+    bz.mint(&proposer, &100);
+    bz.mint(&holder, &40);
+
+    let id = bz.gov.create_proposal(&proposer, &ProposalAction::SetParam(symbol_short!("quorum"), 10), &VotingMode::Token);
+    next_ledger(&env);
+
+    bz.gov.vote(&holder, &id, &true);
+    bz.token.transfer(&holder, &sybil, &40);
+    assert_eq!(bz.token.get_votes(&sybil), 40);
+
+    // The sybil held nothing at the snapshot ledger
+    let result = bz.gov.try_vote(&sybil, &id, &true);
+    assert!(result.is_err());
+    assert_eq!(bz.gov.get_proposal_stats(&id), (40, 0));
+}
+
+#[test]
+fn test_queued_proposal_lapses_after_execution_window() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let bz = setup(&env);
+    let proposer = Address::generate(&env);
+    bz.mint(&proposer, &200);
+
+    let id = bz.gov.create_proposal(&proposer, &ProposalAction::SetParam(symbol_short!("quorum"), 10), &VotingMode::Token);
+    next_ledger(&env);
+    bz.gov.vote(&proposer, &id, &true);
+    env.ledger().with_mut(|li| { li.timestamp += 604_801; });
+    bz.gov.queue(&id);
+
+    env.ledger().with_mut(|li| { li.timestamp += 172_800 + 1_209_601; });
+    bz.gov.execute(&id);
+    assert_eq!(bz.gov.get_proposal(&id).status, ProposalStatus::Expired);
+    assert_eq!(bz.gov.get_param(&symbol_short!("quorum")), None);
+    assert!(bz.gov.try_execute(&id).is_err());
+}
+
+#[test]
+fn test_cancel_proposal() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let bz = setup(&env);
+    let proposer = Address::generate(&env);
+    let stranger = Address::generate(&env);
+    bz.mint(&proposer, &300);
+
+    let withdrawn = bz.gov.create_proposal(&proposer, &ProposalAction::SetParam(symbol_short!("quorum"), 10), &VotingMode::Token);
+    assert!(bz.gov.try_cancel_proposal(&stranger, &withdrawn).is_err());
+    bz.gov.cancel_proposal(&proposer, &withdrawn);
+    assert_eq!(bz.gov.get_proposal(&withdrawn).status, ProposalStatus::Cancelled);
+    next_ledger(&env);
+    assert!(bz.gov.try_vote(&proposer, &withdrawn, &true).is_err());
+
+    // The admin can veto a queued proposal during its timelock
+    let vetoed = bz.gov.create_proposal(&proposer, &ProposalAction::SetParam(symbol_short!("quorum"), 10), &VotingMode::Token);
+    next_ledger(&env);
+    bz.gov.vote(&proposer, &vetoed, &true);
+    env.ledger().with_mut(|li| { li.timestamp += 604_801; });
+    bz.gov.queue(&vetoed);
+    bz.gov.cancel_proposal(&bz.admin, &vetoed);
+    env.ledger().with_mut(|li| { li.timestamp += 172_800; });
+    assert!(bz.gov.try_execute(&vetoed).is_err());
+    assert!(bz.gov.try_cancel_proposal(&proposer, &vetoed).is_err());
+}
+
+#[test]
+fn test_admin_handover_needs_higher_quorum() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let bz = setup(&env);
+    let proposer = Address::generate(&env);
+    let successor = Address::generate(&env);
+    let action = ProposalAction::SetAdmin(bz.gov.address.clone(), successor.clone());
+
+    // Enough for an ordinary proposal, not for a handover
+    bz.mint(&proposer, &600);
+    let id = bz.gov.create_proposal(&proposer, &action, &VotingMode::Token);
+    next_ledger(&env);
+    bz.gov.vote(&proposer, &id, &true);
+    env.ledger().with_mut(|li| { li.timestamp += 604_801; });
+    bz.gov.queue(&id);
+    assert_eq!(bz.gov.get_proposal(&id).status, ProposalStatus::Defeated);
+
+    bz.mint(&proposer, &600);
+    let id = bz.gov.create_proposal(&proposer, &action, &VotingMode::Token);
+    bz.pass(&env, &proposer, id);
+    assert_eq!(bz.gov.get_admin(), successor);
+}
+
+#[test]
+fn test_guard_params_need_higher_quorum() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let bz = setup(&env);
+    let proposer = Address::generate(&env);
+
+    // An ordinary-bar majority cannot lower the sensitive bar or drop the timelock
+    bz.mint(&proposer, &600);
+    for name in ["sens_quor", "sens_thr", "timelock", "exec_win"] {
+        let action = ProposalAction::SetParam(Symbol::new(&env, name), 0);
+        let id = bz.gov.create_proposal(&proposer, &action, &VotingMode::Token);
+        next_ledger(&env);
+        bz.gov.vote(&proposer, &id, &true);
+        env.ledger().with_mut(|li| { li.timestamp += 604_801; });
+        bz.gov.queue(&id);
+        assert_eq!(bz.gov.get_proposal(&id).status, ProposalStatus::Defeated);
+        bz.mint(&proposer, &100); // Replace the proposal fee
+    }
+
+    // Other parameters still pass at the ordinary bar
+    let id = bz.gov.create_proposal(&proposer, &ProposalAction::SetParam(symbol_short!("quorum"), 50), &VotingMode::Token);
+    bz.pass(&env, &proposer, id);
+    assert_eq!(bz.gov.get_proposal(&id).status, ProposalStatus::Executed);
+}
+
+// --- VOTING MODES ---
+
+#[test]
+fn test_quadratic_tally() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let bz = setup(&env);
+    let whale = Address::generate(&env);
+    let small = Address::generate(&env);
+
+    bz.mint(&whale, &10_200);
+    bz.mint(&small, &181);
+    bz.gov.bond_voter(&whale, &100);
+    bz.gov.bond_voter(&small, &100);
+
+    let id = bz.gov.create_proposal(&whale, &ProposalAction::SetParam(symbol_short!("quorum"), 10), &VotingMode::Quadratic);
+    next_ledger(&env);
+    bz.gov.vote(&whale, &id, &true);
+    bz.gov.vote(&small, &id, &false);
+
+    // sqrt(10_000) vs sqrt(81)
+    assert_eq!(bz.gov.get_proposal_stats(&id), (100, 9));
+    assert_eq!(bz.gov.get_proposal(&id).turnout, 10_081);
+
+    // Bonds stay locked until the vote closes and are not treasury money
+    assert_eq!(bz.gov.get_treasury(), 100);
+    assert!(bz.gov.try_unbond_voter(&small).is_err());
+    env.ledger().with_mut(|li| { li.timestamp += 604_801; });
+    assert_eq!(bz.gov.unbond_voter(&small), 100);
+    assert_eq!(bz.token.balance(&small), 181);
+}
+
+#[test]
+#[should_panic(expected = "Quadratic voting requires a bond")]
+fn test_quadratic_requires_bond() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let bz = setup(&env);
+    let proposer = Address::generate(&env);
+
+    bz.mint(&proposer, &200);
+    bz.trust.stake(&proposer, &None); // A free Trust bond does not count
+    bz.gov.bond_voter(&proposer, &50);
+
+    let id = bz.gov.create_proposal(&proposer, &ProposalAction::SetParam(symbol_short!("quorum"), 10), &VotingMode::Quadratic);
+    next_ledger(&env);
+    bz.gov.vote(&proposer, &id, &true);
+}
+
+#[test]
+fn test_trust_weighted_tally() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let bz = setup(&env);
+    let proposer = Address::generate(&env);
+    let merchant = Address::generate(&env);
+    let holder = Address::generate(&env);
+
+    bz.mint(&proposer, &100);
+    bz.trust.stake(&merchant, &None); // Score = 10
+    bz.mint(&merchant, &100);
+    bz.mint(&holder, &100);
+
+    let id = bz.gov.create_proposal(&proposer, &ProposalAction::SetParam(symbol_short!("quorum"), 10), &VotingMode::TrustWeighted);
+    next_ledger(&env);
+    // Trust gained after the proposal was created carries no weight
+    bz.trust.stake(&holder, &None);
+    bz.gov.vote(&merchant, &id, &true);
+    bz.gov.vote(&holder, &id, &false);
+
+    assert_eq!(bz.gov.get_proposal_stats(&id), (110, 100));
+    env.ledger().with_mut(|li| { li.timestamp += 604_801; });
+    bz.gov.queue(&id);
+    assert_eq!(bz.gov.get_proposal(&id).status, ProposalStatus::Queued);
+}
+
+// --- TREASURY MATCHING ---
+
+#[test]
+fn test_treasury_matching() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let bz = setup(&env);
+    let dao = Address::generate(&env);
+    let alice = Address::generate(&env);
+    let school = Address::generate(&env);
+    let name = symbol_short!("school");
+
+    bz.mint(&dao, &300); // 100 fee + 200 voting weight
+    bz.mint(&alice, &100);
+    bz.market.create_campaign(&alice, &name, &school, &150, &1_000_000);
+
+    // Proposal fees fund the treasury: match 1:1 up to 30 per contributor from a 40 BZR pool
+    let rule = MatchingRule { ratio_bps: 10_000, cap_per_contributor: 30, pool: 40 };
+    let id = bz.gov.create_proposal(&dao, &ProposalAction::MatchCampaign(name.clone(), rule), &VotingMode::Token);
+    bz.pass(&env, &dao, id);
+    assert_eq!(bz.gov.get_treasury(), 60);
+    assert_eq!(bz.token.balance(&bz.market.address), 40);
+
+    bz.market.deposit_crowdfund(&alice, &name, &100);
+    bz.market.deposit_crowdfund(&dao, &name, &20);
+    let campaign = bz.market.get_campaign(&name);
+    assert_eq!(campaign.matched, 40); // Alice capped at 30, pool exhausted after 10 more
+    assert_eq!(campaign.match_pool, 0);
+
+    bz.market.claim_campaign(&name);
+    assert_eq!(bz.token.balance(&school), 160);
+}
+
+// --- MULTISIG WALLETS ---
+
+#[test]
+fn test_multisig() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let bz = setup(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+    let target = Address::generate(&env);
+
+    bz.mint(&user1, &100);
+
+    let owners = vec![&env, user1.clone(), user2.clone()];
+    let wid = bz.gov.create_wallet(&user1, &owners, &2);
+
+    bz.gov.deposit_wallet(&user1, &wid, &100);
+    let tx_id = bz.gov.propose_tx(&user1, &wid, &target, &50);
+
+    // User1 approved implicitly or explicitly. Need User2.
+    bz.gov.approve_tx(&user2, &tx_id);
+
+    assert_eq!(bz.token.balance(&target), 50);
+}
+
+#[test]
+fn test_wallet_executes_at_threshold() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let bz = setup(&env);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    let carol = Address::generate(&env);
+    let vendor = Address::generate(&env);
+
+    bz.mint(&alice, &100);
+
+    let owners = vec![&env, alice.clone(), bob.clone(), carol.clone()];
+    let wid = bz.gov.create_wallet(&alice, &owners, &2);
+    bz.gov.deposit_wallet(&alice, &wid, &100);
+
+    let tx_id = bz.gov.propose_tx(&alice, &wid, &vendor, &40);
+    assert_eq!(bz.token.balance(&vendor), 0);
+
+    bz.gov.approve_tx(&bob, &tx_id);
+    assert_eq!(bz.token.balance(&vendor), 40);
+    assert_eq!(bz.gov.get_wallet(&wid).bzr_balance, 60);
+    assert!(bz.gov.get_wallet_tx(&tx_id).executed);
+}
+
+#[test]
+fn test_wallet_funds_are_not_treasury() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let bz = setup(&env);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+
+    bz.mint(&alice, &300);
+    let wid = bz.gov.create_wallet(&alice, &vec![&env, alice.clone(), bob.clone()], &2);
+    bz.gov.deposit_wallet(&alice, &wid, &150);
+    assert_eq!(bz.gov.get_treasury(), 0);
+
+    // A treasury spend cannot reach into wallet reserves
+    let id = bz.gov.create_proposal(&alice, &ProposalAction::TreasurySpend(alice.clone(), 150), &VotingMode::Token);
+    assert_eq!(bz.gov.get_treasury(), 100);
+    next_ledger(&env);
+    bz.gov.vote(&alice, &id, &true);
+    env.ledger().with_mut(|li| { li.timestamp += 604_801; });
+    bz.gov.queue(&id);
+    env.ledger().with_mut(|li| { li.timestamp += 172_800; });
+    assert!(bz.gov.try_execute(&id).is_err());
+    assert_eq!(bz.gov.get_wallet(&wid).bzr_balance, 150);
+}
+
+#[test]
+fn test_wallet_revoke_and_expiry() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let bz = setup(&env);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    let carol = Address::generate(&env);
+    let vendor = Address::generate(&env);
+
+    bz.mint(&alice, &100);
+    let owners = vec![&env, alice.clone(), bob.clone(), carol.clone()];
+    let wid = bz.gov.create_wallet(&alice, &owners, &3);
+    bz.gov.deposit_wallet(&alice, &wid, &100);
+
+    let tx_id = bz.gov.propose_tx(&alice, &wid, &vendor, &40);
+    bz.gov.approve_tx(&bob, &tx_id);
+    bz.gov.revoke_approval(&bob, &tx_id);
+    bz.gov.approve_tx(&carol, &tx_id);
+    assert_eq!(bz.token.balance(&vendor), 0); // Bob's revoked approval no longer counts
+
+    env.ledger().with_mut(|li| { li.timestamp += 604_801; });
+    assert!(bz.gov.try_approve_tx(&bob, &tx_id).is_err());
+}
+
+#[test]
+fn test_wallet_owner_changes_need_threshold() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let bz = setup(&env);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    let carol = Address::generate(&env);
+
+    let owners = vec![&env, alice.clone(), bob.clone()];
+    let wid = bz.gov.create_wallet(&alice, &owners, &2);
+
+    let add = bz.gov.propose_wallet_action(&alice, &wid, &WalletAction::AddOwner(carol.clone()));
+    assert_eq!(bz.gov.get_wallet(&wid).owners.len(), 2);
+    bz.gov.approve_tx(&bob, &add);
+    assert!(bz.gov.get_wallet(&wid).owners.contains(carol.clone()));
+
+    let raise = bz.gov.propose_wallet_action(&carol, &wid, &WalletAction::SetThreshold(3));
+    bz.gov.approve_tx(&alice, &raise);
+    assert_eq!(bz.gov.get_wallet(&wid).threshold, 3);
+
+    // Now 2 of 3 is no longer enough
+    let remove = bz.gov.propose_wallet_action(&alice, &wid, &WalletAction::RemoveOwner(bob.clone()));
+    bz.gov.approve_tx(&carol, &remove);
+    assert!(bz.gov.get_wallet(&wid).owners.contains(bob.clone()));
+}
+
+#[test]
+fn test_pending_tx_executes_after_threshold_drop() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let bz = setup(&env);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    let carol = Address::generate(&env);
+    let vendor = Address::generate(&env);
+
+    let owners = vec![&env, alice.clone(), bob.clone(), carol.clone()];
+    let wid = bz.gov.create_wallet(&alice, &owners, &3);
+    bz.mint(&alice, &100);
+    bz.gov.deposit_wallet(&alice, &wid, &100);
+
+    let pay = bz.gov.propose_tx(&alice, &wid, &vendor, &40);
+    bz.gov.approve_tx(&bob, &pay);
+    assert!(bz.gov.try_execute_tx(&pay).is_err()); // 2 of 3
+
+    let lower = bz.gov.propose_wallet_action(&alice, &wid, &WalletAction::SetThreshold(2));
+    bz.gov.approve_tx(&bob, &lower);
+    bz.gov.approve_tx(&carol, &lower);
+    assert_eq!(bz.gov.get_wallet(&wid).threshold, 2);
+
+    bz.gov.execute_tx(&pay);
+    assert_eq!(bz.token.balance(&vendor), 40);
+    assert!(bz.gov.try_execute_tx(&pay).is_err());
+}
+
+#[test]
+fn test_wallet_holds_external_tokens() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let bz = setup(&env);
+    let issuer = Address::generate(&env);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    let vendor = Address::generate(&env);
+
+    let asset = env.register_stellar_asset_contract_v2(issuer);
+    let usdc = asset.address();
+    soroban_sdk::token::StellarAssetClient::new(&env, &usdc).mint(&alice, &500);
+
+    let owners = vec![&env, alice.clone(), bob.clone()];
+    let wid = bz.gov.create_wallet(&alice, &owners, &2);
+    bz.gov.deposit_wallet_token(&alice, &wid, &usdc, &500);
+    assert_eq!(bz.gov.get_wallet(&wid).tokens.get(usdc.clone()), Some(500));
+
+    let tx_id = bz.gov.propose_wallet_action(&alice, &wid, &WalletAction::TransferToken(usdc.clone(), vendor.clone(), 200));
+    bz.gov.approve_tx(&bob, &tx_id);
+
+    let token = soroban_sdk::token::Client::new(&env, &usdc);
+    assert_eq!(token.balance(&vendor), 200);
+    assert_eq!(bz.gov.get_wallet(&wid).tokens.get(usdc), Some(300));
+}
+
+// --- STORAGE UPKEEP ---
+
+const DAY: u32 = 17_280; // Ledgers
+
+fn ttl_of(env: &Env, bz: &Bazaar, key: &DataKey) -> u32 {
+    env.as_contract(&bz.gov.address, || env.storage().persistent().get_ttl(key))
+}
+
+#[test]
+fn test_proposals_and_wallets_stay_alive() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let bz = setup(&env);
+    let owner = Address::generate(&env);
+    bz.mint(&owner, &200);
+
+    let id = bz.gov.create_proposal(&owner, &ProposalAction::SetParam(symbol_short!("quorum"), 10), &VotingMode::Token);
+    let wid = bz.gov.create_wallet(&owner, &vec![&env, owner.clone()], &1);
+    bz.gov.deposit_wallet(&owner, &wid, &100);
+    assert_eq!(ttl_of(&env, &bz, &DataKey::Proposal(id)), 90 * DAY);
+    assert_eq!(ttl_of(&env, &bz, &DataKey::Wallet(wid)), 90 * DAY);
+
+    // An idle wallet is kept from archival by anyone
+    env.ledger().with_mut(|li| { li.sequence_number += 80 * DAY; });
+    bz.gov.bump_wallet(&wid);
+    bz.gov.bump_proposal(&id);
+    assert_eq!(ttl_of(&env, &bz, &DataKey::Wallet(wid)), 90 * DAY);
+    assert_eq!(ttl_of(&env, &bz, &DataKey::Proposal(id)), 90 * DAY);
+}
@@ -0,0 +1,804 @@
+// 🏛️ PROJECT BAZAAR | MARKET
+// Where BZR changes hands between merchants: Listings, Escrow, Subscription Tiers,
+// Crowdfund Campaigns and the Bazaar Lottery. Fees and house cuts go to the Governance treasury.
+// Listings can be priced in PHP or another oracle asset and are settled in BZR at the oracle rate.
+
+#![no_std]
+use bazaar_types::{Asset, Deployment, MatchingRule, OracleClient, Tier, TierConfig, RECORD_TTL, RECORD_TTL_THRESHOLD};
+use soroban_sdk::xdr::ToXdr;
+use soroban_sdk::{contract, contractimpl, contracttype, token, Address, Bytes, BytesN, Env, IntoVal, Map, String, Symbol, Val, Vec};
+
+// ============================================================
+// 🔧 PROTOCOL CONSTANTS
+// ============================================================
+
+const ESCROW_TIMEOUT: u64 = 1_209_600;  // 14 Days for the seller to confirm before the buyer can reclaim
+const LOTTERY_REVEAL_PERIOD: u64 = 86_400; // 1 Day for commit-reveal players to open their secrets
+const SUBSCRIPTION_PERIOD: u64 = 2_592_000; // 30 Days
+const FREE_LISTING_LIMIT: u32 = 3;
+const FREE_MESSAGE_QUOTA: u32 = 20;     // Messages per day without a subscription
+
+// ============================================================
+// 📦 DATA STRUCTURES
+// ============================================================
+
+#[contracttype]
+#[derive(Clone)]
+pub struct Listing {
+    pub seller: Address,
+    pub title: String,
+    pub price: i128,
+    pub active: bool,
+}
+
+#[contracttype]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum EscrowStatus {
+    Funded,
+    Released,
+    Refunded,
+}
+
+/// BZR held by the Market until both parties approve. `listing` is 0 for direct escrows.
+/// Past `deadline`, a buyer whose seller never approved can take the BZR back.
+#[contracttype]
+#[derive(Clone)]
+pub struct Escrow {
+    pub buyer: Address,
+    pub seller: Address,
+    pub amount: i128,
+    pub listing: u32,
+    pub deadline: u64,
+    pub buyer_approved: bool,
+    pub seller_approved: bool,
+    pub status: EscrowStatus,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct Campaign {
+    pub creator: Address,
+    pub beneficiary: Address,
+    pub goal: i128,
+    pub deadline: u64,
+    pub raised: i128,
+    pub matched: i128,
+    pub match_ratio_bps: i128,
+    pub match_cap: i128,
+    pub match_pool: i128,
+    pub claimed: bool,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct Contribution {
+    pub amount: i128,
+    pub matched: i128,
+}
+
+#[contracttype]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum LotteryStatus {
+    Open,
+    Revealing,
+    Drawn,
+}
+
+/// One lottery round. Kept after the draw as an audit record.
+#[contracttype]
+#[derive(Clone)]
+pub struct LotteryRound {
+    pub id: u32,
+    pub ticket_price: i128,
+    pub max_tickets: u32,
+    pub house_cut_bps: i128,
+    pub commit_reveal: bool,
+    pub tickets: Vec<Address>,
+    pub commits: Map<Address, BytesN<32>>,
+    pub revealed: Vec<Address>,
+    pub seed: BytesN<32>,
+    pub reveal_deadline: u64,
+    pub status: LotteryStatus,
+    pub winner: Option<Address>,
+    pub prize: i128,
+    pub house_cut: i128,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct Subscription {
+    pub tier: Tier,
+    pub expires_at: u64,
+    pub auto_renew: bool,
+}
+
+#[contracttype]
+pub enum DataKey {
+    Admin,
+    Deployment,
+    ListingCount,
+    Listing(u32),
+    ActiveListings(Address),
+    EscrowCount,
+    Escrow(u32),
+    Campaign(Symbol),
+    Contribution(Symbol, Address),
+    LotteryCount,
+    LotteryRound(u32),
+    LotteryCurrent,
+    TierConfig(Tier),
+    Subscription(Address),
+    Oracle,
+    /// Set for listings priced in an asset other than BZR.
+    ListingAsset(u32),
+}
+
+// ============================================================
+// ⚙️ THE CONTRACT
+// ============================================================
+#[contract]
+pub struct MarketContract;
+
+#[contractimpl]
+impl MarketContract {
+
+    pub fn __constructor(env: Env, admin: Address, deployment: Deployment) {
+        env.storage().instance().set(&DataKey::Admin, &admin);
+        env.storage().instance().set(&DataKey::Deployment, &deployment);
+    }
+
+    pub fn get_admin(env: Env) -> Address {
+        env.storage().instance().get(&DataKey::Admin).unwrap()
+    }
+
+    /// Hands the admin seat over, e.g. to the Governance contract.
+    pub fn set_admin(env: Env, new_admin: Address) {
+        require_admin(&env);
+        env.storage().instance().set(&DataKey::Admin, &new_admin);
+    }
+
+    pub fn upgrade(env: Env, new_wasm_hash: BytesN<32>) {
+        require_admin(&env);
+        env.deployer().update_current_contract_wasm(new_wasm_hash);
+    }
+
+    // --- FEATURE 1: LISTINGS ---
+
+    /// Open listings per seller are capped by their tier's `listing_limit`.
+    pub fn create_listing(env: Env, seller: Address, title: String, price: i128) -> u32 {
+        seller.require_auth();
+        list(&env, seller, title, price)
+    }
+
+    /// Lists at `price` units of `asset`, e.g. 1,250 PHP. Buyers pay the BZR equivalent at the
+    /// oracle's rate when they buy.
+    pub fn create_listing_in(env: Env, seller: Address, title: String, price: i128, asset: Asset) -> u32 {
+        seller.require_auth();
+        oracle_price(&env, &asset); // Refuse assets the oracle cannot price
+        let id = list(&env, seller, title, price);
+        store(&env, &DataKey::ListingAsset(id), &asset);
+        id
+    }
+
+    /// The asset a listing's `price` is in; `None` for BZR.
+    pub fn get_listing_asset(env: Env, listing_id: u32) -> Option<Asset> {
+        env.storage().persistent().get(&DataKey::ListingAsset(listing_id))
+    }
+
+    /// What `buy_listing` would escrow right now, in BZR.
+    pub fn get_listing_price(env: Env, listing_id: u32) -> i128 {
+        listing_price(&env, listing_id, &Self::get_listing(env.clone(), listing_id))
+    }
+
+    pub fn set_oracle(env: Env, oracle: Address) {
+        require_admin(&env);
+        store(&env, &DataKey::Oracle, &oracle);
+    }
+
+    pub fn close_listing(env: Env, seller: Address, listing_id: u32) {
+        seller.require_auth();
+        let listing = Self::get_listing(env.clone(), listing_id);
+        if listing.seller != seller { panic!("Not the seller"); }
+        close(&env, listing_id, listing);
+    }
+
+    pub fn get_listing(env: Env, listing_id: u32) -> Listing {
+        env.storage().persistent().get(&DataKey::Listing(listing_id)).expect("Listing not found")
+    }
+
+    /// Takes the listing off the market and escrows its price until the buyer confirms delivery.
+    /// `max_price` caps the BZR paid, since oracle-priced listings convert at execution time.
+    pub fn buy_listing(env: Env, buyer: Address, listing_id: u32, max_price: i128) -> u32 {
+        buyer.require_auth();
+        let listing = Self::get_listing(env.clone(), listing_id);
+        if !listing.active { panic!("Listing closed"); }
+        if listing.seller == buyer { panic!("Cannot buy your own listing"); }
+        let price = listing_price(&env, listing_id, &listing);
+        if price > max_price { panic!("Price above limit"); }
+        let escrow = open_escrow(&env, buyer, listing.seller.clone(), price, listing_id);
+        close(&env, listing_id, listing);
+        escrow
+    }
+
+    // --- FEATURE 2: ESCROW ---
+
+    pub fn create_escrow(env: Env, buyer: Address, seller: Address, amount: i128) -> u32 {
+        buyer.require_auth();
+        if amount <= 0 { panic!("Invalid amount"); }
+        if buyer == seller { panic!("Cannot escrow to yourself"); }
+        open_escrow(&env, buyer, seller, amount, 0)
+    }
+
+    /// Releases to the seller once both buyer and seller have approved.
+    pub fn approve_escrow(env: Env, escrow_id: u32, party: Address) {
+        party.require_auth();
+        let key = DataKey::Escrow(escrow_id);
+        let mut escrow = Self::get_escrow(env.clone(), escrow_id);
+        if escrow.status != EscrowStatus::Funded { panic!("Escrow closed"); }
+        if party == escrow.buyer {
+            escrow.buyer_approved = true;
+        } else if party == escrow.seller {
+            escrow.seller_approved = true;
+        } else {
+            panic!("Not a party to the escrow");
+        }
+
+        if escrow.buyer_approved && escrow.seller_approved {
+            escrow.status = EscrowStatus::Released;
+            bzr(&env).transfer(&env.current_contract_address(), &escrow.seller, &escrow.amount);
+        }
+        store(&env, &key, &escrow);
+    }
+
+    /// The seller can always hand the buyer's BZR back.
+    pub fn refund_escrow(env: Env, escrow_id: u32) {
+        let key = DataKey::Escrow(escrow_id);
+        let mut escrow = Self::get_escrow(env.clone(), escrow_id);
+        escrow.seller.require_auth();
+        if escrow.status != EscrowStatus::Funded { panic!("Escrow closed"); }
+
+        escrow.status = EscrowStatus::Refunded;
+        bzr(&env).transfer(&env.current_contract_address(), &escrow.buyer, &escrow.amount);
+        store(&env, &key, &escrow);
+    }
+
+    /// The buyer's way out when the seller stops responding.
+    pub fn reclaim_escrow(env: Env, escrow_id: u32) {
+        let key = DataKey::Escrow(escrow_id);
+        let mut escrow = Self::get_escrow(env.clone(), escrow_id);
+        escrow.buyer.require_auth();
+        if escrow.status != EscrowStatus::Funded { panic!("Escrow closed"); }
+        if env.ledger().timestamp() <= escrow.deadline { panic!("Escrow still running"); }
+        if escrow.seller_approved { panic!("Seller already approved"); }
+
+        escrow.status = EscrowStatus::Refunded;
+        bzr(&env).transfer(&env.current_contract_address(), &escrow.buyer, &escrow.amount);
+        store(&env, &key, &escrow);
+    }
+
+    pub fn get_escrow(env: Env, escrow_id: u32) -> Escrow {
+        env.storage().persistent().get(&DataKey::Escrow(escrow_id)).expect("Escrow not found")
+    }
+
+    // --- FEATURE 3: CROWDFUND CAMPAIGNS ---
+
+    pub fn create_campaign(env: Env, creator: Address, name: Symbol, beneficiary: Address, goal: i128, deadline: u64) {
+        creator.require_auth();
+        let key = DataKey::Campaign(name);
+        if env.storage().persistent().has(&key) { panic!("Campaign exists"); }
+        if goal <= 0 { panic!("Invalid amount"); }
+        if deadline <= env.ledger().timestamp() { panic!("Deadline in the past"); }
+
+        let campaign = Campaign {
+            creator,
+            beneficiary,
+            goal,
+            deadline,
+            raised: 0,
+            matched: 0,
+            match_ratio_bps: 0,
+            match_cap: 0,
+            match_pool: 0,
+            claimed: false,
+        };
+        store(&env, &key, &campaign);
+    }
+
+    pub fn deposit_crowdfund(env: Env, contributor: Address, name: Symbol, amount: i128) {
+        contributor.require_auth();
+        if amount <= 0 { panic!("Invalid amount"); }
+        let key = DataKey::Campaign(name.clone());
+        let mut campaign: Campaign = env.storage().persistent().get(&key).expect("Campaign not found");
+        if campaign.claimed || env.ledger().timestamp() > campaign.deadline { panic!("Campaign closed"); }
+        bzr(&env).transfer(&contributor, env.current_contract_address(), &amount);
+
+        let contribution_key = DataKey::Contribution(name, contributor);
+        let mut contribution: Contribution = env.storage().persistent().get(&contribution_key).unwrap_or(Contribution { amount: 0, matched: 0 });
+        contribution.amount += amount;
+        campaign.raised += amount;
+
+        // Treasury match, limited by the per-contributor cap and what is left in the reserved pool.
+        let eligible = (campaign.match_cap - contribution.matched).max(0);
+        let matched = (amount * campaign.match_ratio_bps / 10_000).min(eligible).min(campaign.match_pool);
+        contribution.matched += matched;
+        campaign.matched += matched;
+        campaign.match_pool -= matched;
+        store(&env, &contribution_key, &contribution);
+        store(&env, &key, &campaign);
+    }
+
+    /// Beneficiary pulls contributions plus treasury matches once the goal is met.
+    pub fn claim_campaign(env: Env, name: Symbol) {
+        let key = DataKey::Campaign(name);
+        let mut campaign: Campaign = env.storage().persistent().get(&key).expect("Campaign not found");
+        campaign.beneficiary.require_auth();
+        if campaign.claimed { panic!("Already claimed"); }
+        if campaign.raised + campaign.matched < campaign.goal { panic!("Goal not met"); }
+
+        campaign.claimed = true;
+        bzr(&env).transfer(&env.current_contract_address(), &campaign.beneficiary, &(campaign.raised + campaign.matched));
+        pay_treasury(&env, campaign.match_pool);
+        campaign.match_pool = 0;
+        store(&env, &key, &campaign);
+    }
+
+    /// After a missed deadline each contributor pulls their own refund; matches go back to the treasury.
+    pub fn refund_crowdfund(env: Env, contributor: Address, name: Symbol) {
+        contributor.require_auth();
+        let key = DataKey::Campaign(name.clone());
+        let mut campaign: Campaign = env.storage().persistent().get(&key).expect("Campaign not found");
+        if env.ledger().timestamp() <= campaign.deadline { panic!("Campaign still running"); }
+        if campaign.claimed || campaign.raised + campaign.matched >= campaign.goal { panic!("Goal met"); }
+
+        let contribution_key = DataKey::Contribution(name, contributor.clone());
+        let contribution: Contribution = env.storage().persistent().get(&contribution_key).expect("Nothing to refund");
+        env.storage().persistent().remove(&contribution_key);
+        bzr(&env).transfer(&env.current_contract_address(), &contributor, &contribution.amount);
+        pay_treasury(&env, contribution.matched + campaign.match_pool);
+
+        campaign.raised -= contribution.amount;
+        campaign.matched -= contribution.matched;
+        campaign.match_pool = 0;
+        store(&env, &key, &campaign);
+    }
+
+    /// Permissionless after a missed deadline: returns an unspent match pool to the treasury,
+    /// e.g. when nobody contributed and so nobody can trigger `refund_crowdfund`.
+    pub fn reclaim_match_pool(env: Env, name: Symbol) {
+        let key = DataKey::Campaign(name);
+        let mut campaign: Campaign = env.storage().persistent().get(&key).expect("Campaign not found");
+        if env.ledger().timestamp() <= campaign.deadline { panic!("Campaign still running"); }
+        if campaign.claimed || campaign.raised + campaign.matched >= campaign.goal { panic!("Goal met"); }
+        if campaign.match_pool == 0 { panic!("Nothing to reclaim"); }
+
+        pay_treasury(&env, campaign.match_pool);
+        campaign.match_pool = 0;
+        store(&env, &key, &campaign);
+    }
+
+    pub fn get_campaign(env: Env, name: Symbol) -> Campaign {
+        env.storage().persistent().get(&DataKey::Campaign(name)).expect("Campaign not found")
+    }
+
+    pub fn get_contribution(env: Env, name: Symbol, contributor: Address) -> i128 {
+        env.storage().persistent().get::<_, Contribution>(&DataKey::Contribution(name, contributor)).map(|c| c.amount).unwrap_or(0)
+    }
+
+    pub fn get_crowdfund_balance(env: Env, name: Symbol) -> i128 {
+        let campaign = Self::get_campaign(env, name);
+        if campaign.claimed { 0 } else { campaign.raised + campaign.matched }
+    }
+
+    /// Governance only, through a `MatchCampaign` proposal. The new pool has already been
+    /// transferred here; any pool left from a previous rule goes back to the treasury.
+    pub fn set_matching(env: Env, name: Symbol, rule: MatchingRule) {
+        deployment(&env).governance.require_auth();
+        let key = DataKey::Campaign(name);
+        let mut campaign: Campaign = env.storage().persistent().get(&key).expect("Campaign not found");
+        if campaign.claimed { panic!("Campaign closed"); }
+        if rule.ratio_bps < 0 || rule.cap_per_contributor < 0 { panic!("Invalid matching rule"); }
+
+        pay_treasury(&env, campaign.match_pool);
+        campaign.match_ratio_bps = rule.ratio_bps;
+        campaign.match_cap = rule.cap_per_contributor;
+        campaign.match_pool = rule.pool;
+        store(&env, &key, &campaign);
+    }
+
+    // --- FEATURE 4: BAZAAR LOTTERY ---
+
+    /// Opens a new round. In commit-reveal mode the draw is seeded only by player secrets.
+    pub fn start_lottery(env: Env, ticket_price: i128, max_tickets: u32, house_cut_bps: i128, commit_reveal: bool) -> u32 {
+        require_admin(&env);
+        if env.storage().persistent().has(&DataKey::LotteryCurrent) { panic!("Round already open"); }
+        if ticket_price <= 0 || max_tickets == 0 { panic!("Invalid round"); }
+        if !(0..=10_000).contains(&house_cut_bps) { panic!("Invalid house cut"); }
+
+        let id: u32 = env.storage().persistent().get(&DataKey::LotteryCount).unwrap_or(0) + 1;
+        let round = LotteryRound {
+            id,
+            ticket_price,
+            max_tickets,
+            house_cut_bps,
+            commit_reveal,
+            tickets: Vec::new(&env),
+            commits: Map::new(&env),
+            revealed: Vec::new(&env),
+            seed: BytesN::from_array(&env, &[0; 32]),
+            reveal_deadline: 0,
+            status: LotteryStatus::Open,
+            winner: None,
+            prize: 0,
+            house_cut: 0,
+        };
+        store(&env, &DataKey::LotteryRound(id), &round);
+        store(&env, &DataKey::LotteryCount, &id);
+        store(&env, &DataKey::LotteryCurrent, &id);
+        id
+    }
+
+    pub fn buy_ticket(env: Env, user: Address) {
+        user.require_auth();
+        let mut round = current_round(&env);
+        if round.commit_reveal && !round.commits.contains_key(user.clone()) { panic!("Commitment required"); }
+        sell_ticket(&env, &mut round, user);
+    }
+
+    /// `commitment` is sha256(player XDR || round id, big-endian || secret). One commitment per player per round; extra tickets use `buy_ticket`.
+    pub fn buy_ticket_with_commit(env: Env, user: Address, commitment: BytesN<32>) {
+        user.require_auth();
+        let mut round = current_round(&env);
+        if !round.commit_reveal { panic!("Round has no commit phase"); }
+        if round.commits.contains_key(user.clone()) { panic!("Already committed"); }
+        round.commits.set(user.clone(), commitment);
+        sell_ticket(&env, &mut round, user);
+    }
+
+    /// Number of tickets sold in the open round.
+    pub fn get_lottery_info(env: Env) -> u32 {
+        match env.storage().persistent().get::<_, u32>(&DataKey::LotteryCurrent) {
+            Some(id) => Self::get_lottery_round(env, id).tickets.len(),
+            None => 0,
+        }
+    }
+
+    pub fn get_lottery_round(env: Env, id: u32) -> LotteryRound {
+        env.storage().persistent().get(&DataKey::LotteryRound(id)).expect("Round not found")
+    }
+
+    /// Closes ticket sales. PRNG rounds are drawn immediately; commit-reveal rounds open the reveal phase.
+    pub fn run_lottery(env: Env) {
+        require_admin(&env);
+        let mut round = current_round(&env);
+        if round.tickets.is_empty() {
+            settle_round(&env, &mut round, None); // Nothing to draw; close so the next round can start
+            return;
+        }
+
+        if round.commit_reveal {
+            round.status = LotteryStatus::Revealing;
+            round.reveal_deadline = env.ledger().timestamp() + LOTTERY_REVEAL_PERIOD;
+            store(&env, &DataKey::LotteryRound(round.id), &round);
+            return;
+        }
+        let pick = env.prng().gen_range::<u64>(0..round.tickets.len() as u64) as u32;
+        let winner = round.tickets.get(pick).unwrap();
+        settle_round(&env, &mut round, Some(winner));
+    }
+
+    pub fn reveal_ticket(env: Env, user: Address, secret: BytesN<32>) {
+        user.require_auth();
+        let id: u32 = env.storage().persistent().get(&DataKey::LotteryCurrent).expect("No open round");
+        let mut round = Self::get_lottery_round(env.clone(), id);
+        if round.status != LotteryStatus::Revealing { panic!("Not in reveal phase"); }
+        if env.ledger().timestamp() > round.reveal_deadline { panic!("Reveal phase over"); }
+        if round.revealed.contains(user.clone()) { panic!("Already revealed"); }
+
+        let commitment = round.commits.get(user.clone()).expect("No commitment");
+        if ticket_commitment(&env, &user, id, &secret) != commitment { panic!("Commitment mismatch"); }
+        let secret_bytes: Bytes = secret.into();
+
+        // Seed folds every secret together, so no single party controls the outcome.
+        let mut preimage: Bytes = round.seed.clone().into();
+        preimage.append(&secret_bytes);
+        round.seed = env.crypto().sha256(&preimage).to_bytes();
+        round.revealed.push_back(user);
+        store(&env, &DataKey::LotteryRound(id), &round);
+    }
+
+    /// Permissionless after the reveal phase. Tickets whose owner never revealed cannot win;
+    /// if nobody revealed, the pot goes to the treasury. When anyone withheld, the seed may have
+    /// been steered by the last revealer, so the draw falls back to the PRNG.
+    pub fn draw_lottery(env: Env) {
+        let id: u32 = env.storage().persistent().get(&DataKey::LotteryCurrent).expect("No open round");
+        let mut round = Self::get_lottery_round(env.clone(), id);
+        if round.status != LotteryStatus::Revealing { panic!("Not in reveal phase"); }
+        if env.ledger().timestamp() <= round.reveal_deadline { panic!("Reveal phase not over"); }
+
+        let mut eligible: Vec<Address> = Vec::new(&env);
+        for holder in round.tickets.iter() {
+            if round.revealed.contains(holder.clone()) { eligible.push_back(holder); }
+        }
+        if eligible.is_empty() {
+            settle_round(&env, &mut round, None);
+            return;
+        }
+        let pick = if round.revealed.len() == round.commits.len() {
+            let seed = round.seed.to_array();
+            let mut head = [0u8; 8];
+            head.copy_from_slice(&seed[..8]);
+            (u64::from_be_bytes(head) % eligible.len() as u64) as u32
+        } else {
+            env.prng().gen_range::<u64>(0..eligible.len() as u64) as u32
+        };
+        let winner = eligible.get(pick).unwrap();
+        settle_round(&env, &mut round, Some(winner));
+    }
+
+    // --- FEATURE 5: SUBSCRIPTION TIERS ---
+
+    pub fn set_tier(env: Env, tier: Tier, config: TierConfig) {
+        require_admin(&env);
+        if config.price <= 0 || config.duration == 0 { panic!("Invalid tier"); }
+        store(&env, &DataKey::TierConfig(tier), &config);
+    }
+
+    pub fn get_tier(env: Env, tier: Tier) -> TierConfig {
+        tier_config(&env, tier)
+    }
+
+    /// Paying for the current tier extends it; switching tiers starts a fresh term.
+    pub fn subscribe(env: Env, user: Address, tier: Tier) {
+        user.require_auth();
+        let config = tier_config(&env, tier);
+        bzr(&env).transfer(&user, &deployment(&env).governance, &config.price);
+
+        let key = DataKey::Subscription(user);
+        let now = env.ledger().timestamp();
+        let mut sub: Subscription = env.storage().persistent().get(&key).unwrap_or(Subscription { tier, expires_at: now, auto_renew: false });
+        let start = if sub.tier == tier && sub.expires_at > now { sub.expires_at } else { now };
+        sub.tier = tier;
+        sub.expires_at = start + config.duration;
+        store(&env, &key, &sub);
+    }
+
+    pub fn is_subscribed(env: Env, user: Address) -> bool {
+        active_tier(&env, &user).is_some()
+    }
+
+    pub fn get_subscription(env: Env, user: Address) -> Option<Subscription> {
+        env.storage().persistent().get(&DataKey::Subscription(user))
+    }
+
+    /// Perks currently in force for `user`, falling back to the free allowance.
+    pub fn get_perks(env: Env, user: Address) -> TierConfig {
+        perks_of(&env, &user)
+    }
+
+    pub fn set_auto_renew(env: Env, user: Address, enabled: bool) {
+        user.require_auth();
+        let key = DataKey::Subscription(user);
+        let mut sub: Subscription = env.storage().persistent().get(&key).expect("Not subscribed");
+        sub.auto_renew = enabled;
+        store(&env, &key, &sub);
+    }
+
+    /// What `renew` may still pull: the user's BZR allowance for this contract, set with the token's `approve`.
+    pub fn get_renewal_allowance(env: Env, user: Address) -> i128 {
+        bzr(&env).allowance(&user, &env.current_contract_address())
+    }
+
+    /// Permissionless keeper hook: renews an opted-in, expired subscription from the approved allowance.
+    pub fn renew(env: Env, user: Address) {
+        let key = DataKey::Subscription(user.clone());
+        let mut sub: Subscription = env.storage().persistent().get(&key).expect("Not subscribed");
+        if !sub.auto_renew { panic!("Auto-renew disabled"); }
+        let now = env.ledger().timestamp();
+        if now < sub.expires_at { panic!("Subscription still active"); }
+
+        let config = tier_config(&env, sub.tier);
+        let market = env.current_contract_address();
+        let token = bzr(&env);
+        if token.allowance(&user, &market) < config.price { panic!("Allowance exceeded"); }
+        token.transfer_from(&market, &user, &deployment(&env).governance, &config.price);
+
+        sub.expires_at = now + config.duration;
+        store(&env, &key, &sub);
+    }
+
+    // --- FEATURE 6: STORAGE UPKEEP ---
+
+    /// Permissionless: tops up the TTL of `user`'s subscription and listing count, the open
+    /// lottery round and the market's settings, plus the contract itself.
+    pub fn bump(env: Env, user: Address) {
+        env.storage().instance().extend_ttl(RECORD_TTL_THRESHOLD, RECORD_TTL);
+        for key in [
+            DataKey::Subscription(user.clone()),
+            DataKey::ActiveListings(user),
+            DataKey::ListingCount,
+            DataKey::EscrowCount,
+            DataKey::LotteryCount,
+            DataKey::LotteryCurrent,
+            DataKey::Oracle,
+            DataKey::TierConfig(Tier::Basic),
+            DataKey::TierConfig(Tier::Pro),
+            DataKey::TierConfig(Tier::Guild),
+        ] {
+            extend_record(&env, &key);
+        }
+        if let Some(id) = env.storage().persistent().get::<_, u32>(&DataKey::LotteryCurrent) {
+            extend_record(&env, &DataKey::LotteryRound(id));
+        }
+    }
+
+    pub fn bump_listing(env: Env, listing_id: u32) {
+        extend_record(&env, &DataKey::Listing(listing_id));
+        extend_record(&env, &DataKey::ListingAsset(listing_id));
+    }
+
+    pub fn bump_escrow(env: Env, escrow_id: u32) {
+        extend_record(&env, &DataKey::Escrow(escrow_id));
+    }
+
+    pub fn bump_campaign(env: Env, name: Symbol, contributor: Address) {
+        extend_record(&env, &DataKey::Contribution(name.clone(), contributor));
+        extend_record(&env, &DataKey::Campaign(name));
+    }
+}
+
+// ============================================================
+// 🛠️ INTERNAL HELPERS
+// ============================================================
+
+fn require_admin(env: &Env) -> Address {
+    let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+    admin.require_auth();
+    admin
+}
+
+fn deployment(env: &Env) -> Deployment {
+    env.storage().instance().get(&DataKey::Deployment).unwrap()
+}
+
+fn bzr(env: &Env) -> token::Client<'_> {
+    token::Client::new(env, &deployment(env).token)
+}
+
+/// Writes a persistent record and tops up its TTL.
+fn store<V: IntoVal<Env, Val>>(env: &Env, key: &DataKey, value: &V) {
+    env.storage().persistent().set(key, value);
+    extend_record(env, key);
+}
+
+fn extend_record(env: &Env, key: &DataKey) {
+    if !env.storage().persistent().has(key) { return; }
+    env.storage().persistent().extend_ttl(key, RECORD_TTL_THRESHOLD, RECORD_TTL);
+}
+
+/// Sends BZR the Market holds to the Governance treasury.
+fn pay_treasury(env: &Env, amount: i128) {
+    if amount == 0 { return; }
+    bzr(env).transfer(&env.current_contract_address(), &deployment(env).governance, &amount);
+}
+
+fn list(env: &Env, seller: Address, title: String, price: i128) -> u32 {
+    if price <= 0 { panic!("Invalid amount"); }
+    let active_key = DataKey::ActiveListings(seller.clone());
+    let active: u32 = env.storage().persistent().get(&active_key).unwrap_or(0);
+    if active >= perks_of(env, &seller).listing_limit { panic!("Listing limit reached"); }
+
+    let id: u32 = env.storage().persistent().get(&DataKey::ListingCount).unwrap_or(0) + 1;
+    let listing = Listing { seller, title, price, active: true };
+    store(env, &DataKey::Listing(id), &listing);
+    store(env, &DataKey::ListingCount, &id);
+    store(env, &active_key, &(active + 1));
+    id
+}
+
+/// BZR per unit of `asset` from the oracle, with the oracle's decimal scale.
+fn oracle_price(env: &Env, asset: &Asset) -> (i128, i128) {
+    let oracle: Address = env.storage().persistent().get(&DataKey::Oracle).expect("No oracle");
+    let client = OracleClient::new(env, &oracle);
+    let data = client.lastprice(asset).expect("No oracle price");
+    (data.price, 10i128.pow(client.decimals()))
+}
+
+/// A listing's price in BZR, rounded up so the seller never receives less than asked.
+fn listing_price(env: &Env, listing_id: u32, listing: &Listing) -> i128 {
+    match env.storage().persistent().get::<_, Asset>(&DataKey::ListingAsset(listing_id)) {
+        Some(asset) => {
+            let (price, scale) = oracle_price(env, &asset);
+            (listing.price * price + scale - 1) / scale
+        }
+        None => listing.price,
+    }
+}
+
+fn close(env: &Env, listing_id: u32, mut listing: Listing) {
+    if !listing.active { panic!("Listing closed"); }
+    listing.active = false;
+    let active_key = DataKey::ActiveListings(listing.seller.clone());
+    let active: u32 = env.storage().persistent().get(&active_key).unwrap_or(0);
+    store(env, &active_key, &(active - 1));
+    store(env, &DataKey::Listing(listing_id), &listing);
+}
+
+fn open_escrow(env: &Env, buyer: Address, seller: Address, amount: i128, listing: u32) -> u32 {
+    bzr(env).transfer(&buyer, env.current_contract_address(), &amount);
+    let id: u32 = env.storage().persistent().get(&DataKey::EscrowCount).unwrap_or(0) + 1;
+    let escrow = Escrow {
+        buyer,
+        seller,
+        amount,
+        listing,
+        deadline: env.ledger().timestamp() + ESCROW_TIMEOUT,
+        buyer_approved: false,
+        seller_approved: false,
+        status: EscrowStatus::Funded,
+    };
+    store(env, &DataKey::Escrow(id), &escrow);
+    store(env, &DataKey::EscrowCount, &id);
+    id
+}
+
+fn current_round(env: &Env) -> LotteryRound {
+    let id: u32 = env.storage().persistent().get(&DataKey::LotteryCurrent).expect("No open round");
+    let round: LotteryRound = env.storage().persistent().get(&DataKey::LotteryRound(id)).unwrap();
+    if round.status != LotteryStatus::Open { panic!("Ticket sales closed"); }
+    round
+}
+
+/// Binds a lottery secret to its player and round, so a commitment cannot be copied.
+fn ticket_commitment(env: &Env, player: &Address, round_id: u32, secret: &BytesN<32>) -> BytesN<32> {
+    let mut preimage = player.clone().to_xdr(env);
+    preimage.extend_from_array(&round_id.to_be_bytes());
+    preimage.extend_from_array(&secret.to_array());
+    env.crypto().sha256(&preimage).to_bytes()
+}
+
+fn sell_ticket(env: &Env, round: &mut LotteryRound, user: Address) {
+    if round.tickets.len() >= round.max_tickets { panic!("Sold out"); }
+    bzr(env).transfer(&user, env.current_contract_address(), &round.ticket_price);
+    round.tickets.push_back(user);
+    store(env, &DataKey::LotteryRound(round.id), round);
+}
+
+/// Pays the winner, routes the house cut to the treasury and closes the round.
+fn settle_round(env: &Env, round: &mut LotteryRound, winner: Option<Address>) {
+    let pot = round.ticket_price * round.tickets.len() as i128;
+    round.house_cut = pot * round.house_cut_bps / 10_000;
+    round.prize = pot - round.house_cut;
+    match winner.clone() {
+        Some(w) => {
+            bzr(env).transfer(&env.current_contract_address(), &w, &round.prize);
+            pay_treasury(env, round.house_cut);
+        }
+        None => pay_treasury(env, pot),
+    }
+    round.winner = winner;
+    round.status = LotteryStatus::Drawn;
+    store(env, &DataKey::LotteryRound(round.id), round);
+    env.storage().persistent().remove(&DataKey::LotteryCurrent);
+}
+
+fn tier_config(env: &Env, tier: Tier) -> TierConfig {
+    env.storage().persistent().get(&DataKey::TierConfig(tier)).unwrap_or(match tier {
+        Tier::Basic => TierConfig { price: 50, duration: SUBSCRIPTION_PERIOD, listing_limit: 10, message_quota: 100 },
+        Tier::Pro => TierConfig { price: 150, duration: SUBSCRIPTION_PERIOD, listing_limit: 50, message_quota: 500 },
+        Tier::Guild => TierConfig { price: 400, duration: SUBSCRIPTION_PERIOD, listing_limit: 200, message_quota: 2_000 },
+    })
+}
+
+fn active_tier(env: &Env, user: &Address) -> Option<Tier> {
+    let sub: Subscription = env.storage().persistent().get(&DataKey::Subscription(user.clone()))?;
+    if env.ledger().timestamp() < sub.expires_at { Some(sub.tier) } else { None }
+}
+
+fn perks_of(env: &Env, user: &Address) -> TierConfig {
+    match active_tier(env, user) {
+        Some(tier) => tier_config(env, tier),
+        None => TierConfig { price: 0, duration: 0, listing_limit: FREE_LISTING_LIMIT, message_quota: FREE_MESSAGE_QUOTA },
+    }
+}
+
+mod test;
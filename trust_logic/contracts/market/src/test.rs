@@ -0,0 +1,621 @@
+#![cfg(test)]
+
+use super::*;
+use bazaar_oracle::{OracleConfig, OracleContract, OracleContractClient};
+use bazaar_token::{BzrToken, BzrTokenClient};
+use soroban_sdk::{
+    symbol_short,
+    testutils::{storage::Persistent as _, Address as _, Ledger},
+    Address, BytesN, Env, String,
+};
+
+struct Bazaar {
+    admin: Address,
+    market: MarketContractClient<'static>,
+    token: BzrTokenClient<'static>,
+    /// Stands in for the Governance contract, i.e. the treasury.
+    treasury: Address,
+}
+
+impl Bazaar {
+    fn mint(&self, to: &Address, amount: &i128) {
+        self.token.mint(&self.admin, to, amount);
+    }
+}
+
+fn setup(env: &Env) -> Bazaar {
+    let admin = Address::generate(env);
+    let token = BzrTokenClient::new(env, &env.register(BzrToken, (&admin,)));
+    let treasury = Address::generate(env);
+    let deployment = Deployment {
+        trust: Address::generate(env),
+        vault: Address::generate(env),
+        token: token.address.clone(),
+        market: Address::generate(env),
+        governance: treasury.clone(),
+    };
+    let market = MarketContractClient::new(env, &env.register_at(&deployment.market, MarketContract, (&admin, &deployment)));
+    Bazaar { admin, market, token, treasury }
+}
+
+// --- LISTINGS ---
+
+#[test]
+fn test_listing_sale_goes_through_escrow() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let bz = setup(&env);
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    bz.mint(&buyer, &80);
+
+    let id = bz.market.create_listing(&seller, &String::from_str(&env, "Woven basket"), &80);
+    let escrow_id = bz.market.buy_listing(&buyer, &id, &80);
+    assert!(!bz.market.get_listing(&id).active);
+    assert_eq!(bz.market.get_escrow(&escrow_id).listing, id);
+    assert_eq!(bz.token.balance(&bz.market.address), 80);
+
+    bz.market.approve_escrow(&escrow_id, &buyer);
+    bz.market.approve_escrow(&escrow_id, &seller);
+    assert_eq!(bz.token.balance(&seller), 80);
+    assert_eq!(bz.market.get_escrow(&escrow_id).status, EscrowStatus::Released);
+}
+
+#[test]
+fn test_listing_limit_follows_tier() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let bz = setup(&env);
+    let seller = Address::generate(&env);
+    let title = String::from_str(&env, "Mangoes");
+
+    for _ in 0..3 { bz.market.create_listing(&seller, &title, &5); }
+    assert!(bz.market.try_create_listing(&seller, &title, &5).is_err());
+
+    // Closing one frees a slot, and so does subscribing
+    bz.market.close_listing(&seller, &1);
+    bz.market.create_listing(&seller, &title, &5);
+    bz.mint(&seller, &50);
+    bz.market.subscribe(&seller, &Tier::Basic);
+    bz.market.create_listing(&seller, &title, &5);
+}
+
+#[test]
+#[should_panic(expected = "Cannot buy your own listing")]
+fn test_seller_cannot_buy_own_listing() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let bz = setup(&env);
+    let seller = Address::generate(&env);
+    bz.mint(&seller, &10);
+
+    let id = bz.market.create_listing(&seller, &String::from_str(&env, "Rice"), &10);
+    bz.market.buy_listing(&seller, &id, &10);
+}
+
+#[test]
+#[should_panic(expected = "Not the seller")]
+fn test_only_seller_closes_listing() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let bz = setup(&env);
+    let seller = Address::generate(&env);
+    let stranger = Address::generate(&env);
+
+    let id = bz.market.create_listing(&seller, &String::from_str(&env, "Rice"), &10);
+    bz.market.close_listing(&stranger, &id);
+}
+
+/// An oracle quoting PHP at `bzr_per_php` (7 decimals) from a single reporter, wired into the Market.
+fn php_oracle(env: &Env, bz: &Bazaar, bzr_per_php: i128) -> (OracleContractClient<'static>, Address) {
+    let config = OracleConfig { max_age: 3_600, min_reports: 1 };
+    let oracle_id = env.register(OracleContract, (&bz.admin, Asset::Stellar(bz.token.address.clone()), config));
+    let oracle = OracleContractClient::new(env, &oracle_id);
+    let reporter = Address::generate(env);
+    oracle.set_asset(&Asset::Other(symbol_short!("PHP")), &true);
+    oracle.set_reporter(&reporter, &true);
+    oracle.submit_price(&reporter, &Asset::Other(symbol_short!("PHP")), &bzr_per_php, &env.ledger().timestamp());
+    bz.market.set_oracle(&oracle.address);
+    (oracle, reporter)
+}
+
+#[test]
+fn test_php_listing_settles_in_bzr() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| { li.timestamp = 1_700_000_000; });
+    let bz = setup(&env);
+    let (oracle, reporter) = php_oracle(&env, &bz, 1_000_000); // 1 PHP = 0.10 BZR
+    let php = Asset::Other(symbol_short!("PHP"));
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    bz.mint(&buyer, &200);
+
+    // Premium Jasmine Rice (25kg), RWA-001 in the merchant inventory
+    let id = bz.market.create_listing_in(&seller, &String::from_str(&env, "Jasmine Rice 25kg"), &1_250, &php);
+    assert_eq!(bz.market.get_listing_asset(&id), Some(php.clone()));
+    assert_eq!(bz.market.get_listing_price(&id), 125);
+
+    // The rate moves before the sale; the escrow follows it, rounded up for the seller
+    env.ledger().with_mut(|li| { li.timestamp += 60; });
+    oracle.submit_price(&reporter, &php, &1_100_001, &env.ledger().timestamp());
+    let escrow_id = bz.market.buy_listing(&buyer, &id, &140);
+    assert_eq!(bz.market.get_escrow(&escrow_id).amount, 138);
+    assert_eq!(bz.token.balance(&buyer), 62);
+}
+
+#[test]
+#[should_panic(expected = "No oracle price")]
+fn test_php_listing_needs_fresh_price() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| { li.timestamp = 1_700_000_000; });
+    let bz = setup(&env);
+    php_oracle(&env, &bz, 1_000_000);
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    bz.mint(&buyer, &200);
+
+    let id = bz.market.create_listing_in(&seller, &String::from_str(&env, "Tech bundle"), &500, &Asset::Other(symbol_short!("PHP")));
+    env.ledger().with_mut(|li| { li.timestamp += 3_601; });
+    bz.market.buy_listing(&buyer, &id, &50);
+}
+
+#[test]
+#[should_panic(expected = "Price above limit")]
+fn test_php_listing_respects_max_price() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| { li.timestamp = 1_700_000_000; });
+    let bz = setup(&env);
+    let (oracle, reporter) = php_oracle(&env, &bz, 1_000_000);
+    let php = Asset::Other(symbol_short!("PHP"));
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    bz.mint(&buyer, &500);
+
+    // The buyer quotes 125 BZR, then the rate doubles before the purchase lands
+    let id = bz.market.create_listing_in(&seller, &String::from_str(&env, "Jasmine Rice 25kg"), &1_250, &php);
+    let quote = bz.market.get_listing_price(&id);
+    env.ledger().with_mut(|li| { li.timestamp += 60; });
+    oracle.submit_price(&reporter, &php, &2_000_000, &env.ledger().timestamp());
+    bz.market.buy_listing(&buyer, &id, &quote);
+}
+
+// --- ESCROW ---
+
+#[test]
+fn test_escrow_flow() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let bz = setup(&env);
+    let buyer = Address::generate(&env);
+    let seller = Address::generate(&env);
+    bz.mint(&buyer, &100);
+
+    let id = bz.market.create_escrow(&buyer, &seller, &50);
+    bz.market.approve_escrow(&id, &buyer); // Buyer approves
+    bz.market.approve_escrow(&id, &seller); // Seller approves -> Release
+
+    assert_eq!(bz.token.balance(&seller), 50);
+    assert_eq!(bz.token.balance(&buyer), 50);
+}
+
+#[test]
+fn test_seller_refunds_escrow() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let bz = setup(&env);
+    let buyer = Address::generate(&env);
+    let seller = Address::generate(&env);
+    bz.mint(&buyer, &50);
+
+    let id = bz.market.create_escrow(&buyer, &seller, &50);
+    bz.market.refund_escrow(&id);
+    assert_eq!(bz.token.balance(&buyer), 50);
+    assert_eq!(bz.market.get_escrow(&id).status, EscrowStatus::Refunded);
+    assert!(bz.market.try_approve_escrow(&id, &buyer).is_err());
+}
+
+#[test]
+fn test_buyer_reclaims_after_deadline() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let bz = setup(&env);
+    let buyer = Address::generate(&env);
+    let seller = Address::generate(&env);
+    bz.mint(&buyer, &50);
+
+    let id = bz.market.create_escrow(&buyer, &seller, &50);
+    bz.market.approve_escrow(&id, &buyer);
+    assert!(bz.market.try_reclaim_escrow(&id).is_err()); // Seller still has time
+
+    // The seller goes quiet past the deadline
+    env.ledger().with_mut(|li| { li.timestamp += 1_209_601; });
+    bz.market.reclaim_escrow(&id);
+    assert_eq!(bz.token.balance(&buyer), 50);
+    assert_eq!(bz.market.get_escrow(&id).status, EscrowStatus::Refunded);
+}
+
+#[test]
+#[should_panic(expected = "Seller already approved")]
+fn test_no_reclaim_once_seller_approved() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let bz = setup(&env);
+    let buyer = Address::generate(&env);
+    let seller = Address::generate(&env);
+    bz.mint(&buyer, &50);
+
+    let id = bz.market.create_escrow(&buyer, &seller, &50);
+    bz.market.approve_escrow(&id, &seller);
+    env.ledger().with_mut(|li| { li.timestamp += 1_209_601; });
+    bz.market.reclaim_escrow(&id);
+}
+
+#[test]
+#[should_panic(expected = "Not a party to the escrow")]
+fn test_outsider_cannot_approve_escrow() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let bz = setup(&env);
+    let buyer = Address::generate(&env);
+    let seller = Address::generate(&env);
+    bz.mint(&buyer, &50);
+
+    let id = bz.market.create_escrow(&buyer, &seller, &50);
+    bz.market.approve_escrow(&id, &Address::generate(&env));
+}
+
+// --- CROWDFUND CAMPAIGNS ---
+
+#[test]
+fn test_crowdfund() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let bz = setup(&env);
+    let user = Address::generate(&env);
+    let target = Address::generate(&env);
+    bz.mint(&user, &20);
+
+    let campaign = symbol_short!("well");
+    bz.market.create_campaign(&user, &campaign, &target, &100, &1000);
+    bz.market.deposit_crowdfund(&user, &campaign, &15);
+
+    assert_eq!(bz.token.balance(&user), 5);
+    assert_eq!(bz.market.get_crowdfund_balance(&campaign), 15);
+}
+
+#[test]
+fn test_campaign_goal_met_and_claimed() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let bz = setup(&env);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    let school = Address::generate(&env);
+    let name = symbol_short!("school");
+
+    bz.mint(&alice, &60);
+    bz.mint(&bob, &60);
+
+    bz.market.create_campaign(&alice, &name, &school, &100, &1000);
+    bz.market.deposit_crowdfund(&alice, &name, &50);
+    bz.market.deposit_crowdfund(&bob, &name, &50);
+    assert_eq!(bz.market.get_contribution(&name, &alice), 50);
+    assert_eq!(bz.market.get_crowdfund_balance(&name), 100);
+
+    bz.market.claim_campaign(&name);
+    assert_eq!(bz.token.balance(&school), 100);
+    assert_eq!(bz.market.get_crowdfund_balance(&name), 0);
+}
+
+#[test]
+fn test_campaign_refund_after_deadline() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let bz = setup(&env);
+    let alice = Address::generate(&env);
+    let school = Address::generate(&env);
+    let name = symbol_short!("school");
+
+    bz.mint(&alice, &60);
+    bz.market.create_campaign(&alice, &name, &school, &100, &1000);
+    bz.market.deposit_crowdfund(&alice, &name, &60);
+    assert!(bz.market.try_claim_campaign(&name).is_err());
+
+    env.ledger().with_mut(|li| { li.timestamp = 1001; });
+    bz.market.refund_crowdfund(&alice, &name);
+    assert_eq!(bz.token.balance(&alice), 60);
+    assert_eq!(bz.market.get_crowdfund_balance(&name), 0);
+}
+
+#[test]
+fn test_new_matching_rule_returns_old_pool() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let bz = setup(&env);
+    let alice = Address::generate(&env);
+    let school = Address::generate(&env);
+    let name = symbol_short!("school");
+
+    bz.mint(&alice, &100);
+    bz.market.create_campaign(&alice, &name, &school, &150, &1_000_000);
+
+    // Governance moves each pool over before setting the rule
+    bz.mint(&bz.market.address, &40);
+    bz.market.set_matching(&name, &MatchingRule { ratio_bps: 10_000, cap_per_contributor: 30, pool: 40 });
+    bz.mint(&bz.market.address, &10);
+    bz.market.set_matching(&name, &MatchingRule { ratio_bps: 5_000, cap_per_contributor: 30, pool: 10 });
+    assert_eq!(bz.token.balance(&bz.treasury), 40);
+
+    bz.market.deposit_crowdfund(&alice, &name, &100);
+    let campaign = bz.market.get_campaign(&name);
+    assert_eq!(campaign.matched, 10); // Half of 30 wanted, pool only had 10
+    assert_eq!(campaign.match_pool, 0);
+    assert_eq!(bz.market.get_crowdfund_balance(&name), 110);
+}
+
+#[test]
+fn test_unused_match_pool_returns_to_treasury() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let bz = setup(&env);
+    let alice = Address::generate(&env);
+    let school = Address::generate(&env);
+    let name = symbol_short!("school");
+
+    bz.market.create_campaign(&alice, &name, &school, &150, &1000);
+    bz.mint(&bz.market.address, &40);
+    bz.market.set_matching(&name, &MatchingRule { ratio_bps: 10_000, cap_per_contributor: 30, pool: 40 });
+    assert!(bz.market.try_reclaim_match_pool(&name).is_err());
+
+    // Nobody contributed, so there is no refund to sweep the pool along with it
+    env.ledger().with_mut(|li| { li.timestamp = 1001; });
+    bz.market.reclaim_match_pool(&name);
+    assert_eq!(bz.token.balance(&bz.treasury), 40);
+    assert_eq!(bz.market.get_campaign(&name).match_pool, 0);
+    assert!(bz.market.try_reclaim_match_pool(&name).is_err());
+}
+
+// --- BAZAAR LOTTERY ---
+
+#[test]
+fn test_lottery() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let bz = setup(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+    bz.mint(&user1, &20);
+    bz.mint(&user2, &20);
+
+    bz.market.start_lottery(&10, &100, &0, &false);
+    bz.market.buy_ticket(&user1);
+    bz.market.buy_ticket(&user2);
+
+    assert_eq!(bz.market.get_lottery_info(), 2);
+    assert_eq!(bz.token.balance(&user1), 10);
+
+    bz.market.run_lottery();
+
+    // One should have 10 (loser) + 0, one should have 10 (winner) + 20 = 30
+    let bal1 = bz.token.balance(&user1);
+    let bal2 = bz.token.balance(&user2);
+
+    assert!((bal1 == 30 && bal2 == 10) || (bal1 == 10 && bal2 == 30));
+    assert_eq!(bz.market.get_lottery_info(), 0);
+}
+
+#[test]
+fn test_lottery_house_cut_and_history() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let bz = setup(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+    bz.mint(&user1, &20);
+    bz.mint(&user2, &20);
+
+    let id = bz.market.start_lottery(&10, &3, &1_000, &false);
+    bz.market.buy_ticket(&user1);
+    bz.market.buy_ticket(&user1);
+    bz.market.buy_ticket(&user2);
+    assert!(bz.market.try_buy_ticket(&user2).is_err()); // Sold out
+    assert_eq!(bz.market.get_lottery_info(), 3);
+
+    bz.market.run_lottery();
+    assert_eq!(bz.market.get_lottery_info(), 0);
+    assert_eq!(bz.token.balance(&bz.treasury), 3);
+
+    let round = bz.market.get_lottery_round(&id);
+    assert_eq!(round.status, LotteryStatus::Drawn);
+    assert_eq!(round.prize, 27);
+    let winner = round.winner.unwrap();
+    assert!(winner == user1 || winner == user2);
+    assert_eq!(bz.token.balance(&user1) + bz.token.balance(&user2), 37);
+}
+
+/// What a player commits to for a secret of 32 copies of `b`.
+fn ticket_hash(env: &Env, player: &Address, round: u32, b: u8) -> BytesN<32> {
+    let mut preimage = player.clone().to_xdr(env);
+    preimage.extend_from_array(&round.to_be_bytes());
+    preimage.extend_from_array(&[b; 32]);
+    env.crypto().sha256(&preimage).to_bytes()
+}
+
+#[test]
+fn test_lottery_commit_reveal() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let bz = setup(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+    let user3 = Address::generate(&env);
+    bz.mint(&user1, &10);
+    bz.mint(&user2, &10);
+    bz.mint(&user3, &10);
+
+    let secret = |b: u8| BytesN::from_array(&env, &[b; 32]);
+
+    let id = bz.market.start_lottery(&10, &10, &0, &true);
+    assert!(bz.market.try_buy_ticket(&user1).is_err()); // Commitment required
+    bz.market.buy_ticket_with_commit(&user1, &ticket_hash(&env, &user1, id, 1));
+    bz.market.buy_ticket_with_commit(&user2, &ticket_hash(&env, &user2, id, 2));
+    bz.market.buy_ticket_with_commit(&user3, &ticket_hash(&env, &user3, id, 3));
+
+    bz.market.run_lottery();
+    assert_eq!(bz.market.get_lottery_round(&id).status, LotteryStatus::Revealing);
+    assert!(bz.market.try_reveal_ticket(&user1, &secret(9)).is_err());
+    bz.market.reveal_ticket(&user1, &secret(1));
+    bz.market.reveal_ticket(&user2, &secret(2));
+    // user3 withholds and forfeits
+
+    assert!(bz.market.try_draw_lottery().is_err());
+    env.ledger().with_mut(|li| { li.timestamp += 86_401; });
+    bz.market.draw_lottery();
+
+    let winner = bz.market.get_lottery_round(&id).winner.unwrap();
+    assert!(winner == user1 || winner == user2);
+    assert_eq!(bz.token.balance(&winner), 30);
+}
+
+#[test]
+fn test_copied_lottery_commitment_does_not_reveal() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let bz = setup(&env);
+    let honest = Address::generate(&env);
+    let copycat = Address::generate(&env);
+    bz.mint(&honest, &10);
+    bz.mint(&copycat, &10);
+
+    let id = bz.market.start_lottery(&10, &10, &0, &true);
+    let commitment = ticket_hash(&env, &honest, id, 7);
+    bz.market.buy_ticket_with_commit(&honest, &commitment);
+    bz.market.buy_ticket_with_commit(&copycat, &commitment);
+    bz.market.run_lottery();
+
+    bz.market.reveal_ticket(&honest, &BytesN::from_array(&env, &[7; 32]));
+    assert!(bz.market.try_reveal_ticket(&copycat, &BytesN::from_array(&env, &[7; 32])).is_err());
+
+    env.ledger().with_mut(|li| { li.timestamp += 86_401; });
+    bz.market.draw_lottery();
+    assert_eq!(bz.market.get_lottery_round(&id).winner, Some(honest));
+}
+
+#[test]
+fn test_empty_lottery_round_closes() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let bz = setup(&env);
+
+    let id = bz.market.start_lottery(&10, &10, &500, &false);
+    bz.market.run_lottery();
+    let round = bz.market.get_lottery_round(&id);
+    assert_eq!(round.status, LotteryStatus::Drawn);
+    assert_eq!(round.winner, None);
+    assert_eq!(bz.market.start_lottery(&10, &10, &500, &true), id + 1);
+}
+
+// --- SUBSCRIPTION TIERS ---
+
+#[test]
+fn test_subscription() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let bz = setup(&env);
+    let user = Address::generate(&env);
+    bz.mint(&user, &50);
+
+    assert!(!bz.market.is_subscribed(&user));
+    bz.market.subscribe(&user, &Tier::Basic);
+    assert!(bz.market.is_subscribed(&user));
+    assert_eq!(bz.token.balance(&user), 0);
+}
+
+#[test]
+fn test_subscription_expires() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let bz = setup(&env);
+    let user = Address::generate(&env);
+    bz.mint(&user, &300);
+    assert_eq!(bz.market.get_perks(&user).listing_limit, 3);
+
+    bz.market.subscribe(&user, &Tier::Pro);
+    assert!(bz.market.is_subscribed(&user));
+    assert_eq!(bz.market.get_perks(&user).listing_limit, 50);
+    assert_eq!(bz.token.balance(&bz.treasury), 150);
+
+    // Paying again for the same tier stacks the term
+    bz.market.subscribe(&user, &Tier::Pro);
+    env.ledger().with_mut(|li| { li.timestamp += 2_592_000 * 2 - 1; });
+    assert!(bz.market.is_subscribed(&user));
+    env.ledger().with_mut(|li| { li.timestamp += 1; });
+    assert!(!bz.market.is_subscribed(&user));
+    assert_eq!(bz.market.get_perks(&user).message_quota, 20);
+}
+
+#[test]
+fn test_auto_renew_uses_allowance() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let bz = setup(&env);
+    let user = Address::generate(&env);
+
+    bz.market.set_tier(&Tier::Guild, &TierConfig { price: 40, duration: 1_000, listing_limit: 500, message_quota: 5_000 });
+    bz.mint(&user, &200);
+
+    bz.market.subscribe(&user, &Tier::Guild);
+    assert!(bz.market.try_renew(&user).is_err()); // Not opted in
+
+    bz.market.set_auto_renew(&user, &true);
+    bz.token.approve(&user, &bz.market.address, &50, &(env.ledger().sequence() + 100));
+    env.ledger().with_mut(|li| { li.timestamp += 1_000; });
+    bz.market.renew(&user);
+    assert!(bz.market.is_subscribed(&user));
+    assert_eq!(bz.market.get_renewal_allowance(&user), 10);
+    assert_eq!(bz.token.balance(&user), 120);
+    assert_eq!(bz.token.balance(&bz.treasury), 80);
+
+    // Allowance no longer covers the price
+    env.ledger().with_mut(|li| { li.timestamp += 1_000; });
+    assert!(bz.market.try_renew(&user).is_err());
+    assert!(!bz.market.is_subscribed(&user));
+}
+
+// --- STORAGE UPKEEP ---
+
+const DAY: u32 = 17_280; // Ledgers
+
+fn ttl_of(env: &Env, bz: &Bazaar, key: &DataKey) -> u32 {
+    env.as_contract(&bz.market.address, || env.storage().persistent().get_ttl(key))
+}
+
+#[test]
+fn test_escrows_campaigns_and_subscriptions_stay_alive() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let bz = setup(&env);
+    let buyer = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let name = symbol_short!("school");
+    bz.mint(&buyer, &200);
+
+    let id = bz.market.create_escrow(&buyer, &seller, &50);
+    bz.market.create_campaign(&buyer, &name, &seller, &100, &1000);
+    bz.market.subscribe(&buyer, &Tier::Basic);
+    let records = [DataKey::Escrow(id), DataKey::Campaign(name.clone()), DataKey::Subscription(buyer.clone())];
+    for key in records.iter() {
+        assert_eq!(ttl_of(&env, &bz, key), 90 * DAY);
+    }
+
+    env.ledger().with_mut(|li| { li.sequence_number += 80 * DAY; });
+    bz.market.bump(&buyer);
+    bz.market.bump_escrow(&id);
+    bz.market.bump_campaign(&name, &buyer);
+    for key in records.iter() {
+        assert_eq!(ttl_of(&env, &bz, key), 90 * DAY);
+    }
+}
@@ -0,0 +1,226 @@
+// 🏛️ PROJECT BAZAAR | PRICE ORACLE
+// Prices of PHP, Pi and other assets in BZR, reported by authorized feeders and read through a
+// SEP-40 style `lastprice`. Each read takes the median of the fresh reports, so one bad or
+// silent reporter cannot move the rate the Market settles at.
+
+#![no_std]
+use bazaar_types::{Asset, PriceData, RECORD_TTL, RECORD_TTL_THRESHOLD};
+use soroban_sdk::{contract, contractimpl, contracttype, Address, BytesN, Env, IntoVal, Val, Vec};
+
+// ============================================================
+// 🔧 PROTOCOL CONSTANTS
+// ============================================================
+
+const DECIMALS: u32 = 7;          // 1 BZR = 10_000_000
+const RESOLUTION: u32 = 300;      // Expected reporting cadence, in seconds
+
+// ============================================================
+// 📦 DATA STRUCTURES
+// ============================================================
+
+#[contracttype]
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct OracleConfig {
+    /// Reports older than this many seconds are ignored, and refused on submission.
+    pub max_age: u64,
+    /// Fresh reports needed before `lastprice` answers.
+    pub min_reports: u32,
+}
+
+#[contracttype]
+pub enum DataKey {
+    Admin,
+    Config,
+    Base,
+    Assets,
+    Reporters,
+    Report(Asset, Address),
+}
+
+// ============================================================
+// ⚙️ THE CONTRACT
+// ============================================================
+#[contract]
+pub struct OracleContract;
+
+#[contractimpl]
+impl OracleContract {
+
+    /// `base` is the asset prices are quoted in, BZR for the Bazaar.
+    pub fn __constructor(env: Env, admin: Address, base: Asset, config: OracleConfig) {
+        validate_config(&config);
+        env.storage().instance().set(&DataKey::Admin, &admin);
+        store(&env, &DataKey::Base, &base);
+        store(&env, &DataKey::Config, &config);
+    }
+
+    pub fn get_admin(env: Env) -> Address {
+        env.storage().instance().get(&DataKey::Admin).unwrap()
+    }
+
+    pub fn set_admin(env: Env, new_admin: Address) {
+        require_admin(&env);
+        env.storage().instance().set(&DataKey::Admin, &new_admin);
+    }
+
+    pub fn set_config(env: Env, config: OracleConfig) {
+        require_admin(&env);
+        validate_config(&config);
+        store(&env, &DataKey::Config, &config);
+    }
+
+    pub fn get_config(env: Env) -> OracleConfig {
+        config(&env)
+    }
+
+    pub fn upgrade(env: Env, new_wasm_hash: BytesN<32>) {
+        require_admin(&env);
+        env.deployer().update_current_contract_wasm(new_wasm_hash);
+    }
+
+    // --- FEATURE 1: REPORTERS ---
+
+    pub fn set_reporter(env: Env, reporter: Address, enabled: bool) {
+        require_admin(&env);
+        let mut reporters = reporters(&env);
+        match (reporters.first_index_of(&reporter), enabled) {
+            (None, true) => reporters.push_back(reporter),
+            (Some(i), false) => { reporters.remove(i); }
+            _ => return,
+        }
+        store(&env, &DataKey::Reporters, &reporters);
+    }
+
+    pub fn get_reporters(env: Env) -> Vec<Address> {
+        reporters(&env)
+    }
+
+    pub fn set_asset(env: Env, asset: Asset, enabled: bool) {
+        require_admin(&env);
+        let mut assets = Self::assets(env.clone());
+        match (assets.first_index_of(&asset), enabled) {
+            (None, true) => assets.push_back(asset),
+            (Some(i), false) => { assets.remove(i); }
+            _ => return,
+        }
+        store(&env, &DataKey::Assets, &assets);
+    }
+
+    /// `price` is one unit of `asset` in the base asset, scaled by `decimals()`, as observed at `timestamp`.
+    /// Each reporter's newer report replaces its last one.
+    pub fn submit_price(env: Env, reporter: Address, asset: Asset, price: i128, timestamp: u64) {
+        reporter.require_auth();
+        if !reporters(&env).contains(&reporter) { panic!("Not a reporter"); }
+        if !Self::assets(env.clone()).contains(&asset) { panic!("Asset not supported"); }
+        if price <= 0 { panic!("Invalid price"); }
+        let now = env.ledger().timestamp();
+        if timestamp > now { panic!("Report from the future"); }
+        if now - timestamp > config(&env).max_age { panic!("Stale report"); }
+
+        let key = DataKey::Report(asset, reporter);
+        if let Some(last) = env.storage().persistent().get::<_, PriceData>(&key) {
+            if timestamp <= last.timestamp { panic!("Stale report"); }
+        }
+        store(&env, &key, &PriceData { price, timestamp });
+    }
+
+    pub fn get_report(env: Env, asset: Asset, reporter: Address) -> Option<PriceData> {
+        env.storage().persistent().get(&DataKey::Report(asset, reporter))
+    }
+
+    // --- FEATURE 2: SEP-40 INTERFACE ---
+
+    pub fn base(env: Env) -> Asset {
+        env.storage().persistent().get(&DataKey::Base).expect("Not initialized")
+    }
+
+    pub fn assets(env: Env) -> Vec<Asset> {
+        env.storage().persistent().get(&DataKey::Assets).unwrap_or(Vec::new(&env))
+    }
+
+    pub fn decimals(_env: Env) -> u32 {
+        DECIMALS
+    }
+
+    pub fn resolution(_env: Env) -> u32 {
+        RESOLUTION
+    }
+
+    /// Median of the current reporters' fresh reports, stamped with the oldest report used.
+    /// `None` when fewer than `min_reports` are fresh.
+    pub fn lastprice(env: Env, asset: Asset) -> Option<PriceData> {
+        let config = config(&env);
+        let now = env.ledger().timestamp();
+        let mut prices: Vec<i128> = Vec::new(&env);
+        let mut oldest = now;
+        for reporter in reporters(&env).iter() {
+            let report: Option<PriceData> = env.storage().persistent().get(&DataKey::Report(asset.clone(), reporter));
+            if let Some(report) = report {
+                if now - report.timestamp > config.max_age { continue; }
+                insert_sorted(&mut prices, report.price);
+                oldest = oldest.min(report.timestamp);
+            }
+        }
+        if prices.is_empty() || prices.len() < config.min_reports { return None; }
+
+        let mid = prices.len() / 2;
+        let price = if prices.len() % 2 == 1 {
+            prices.get_unchecked(mid)
+        } else {
+            (prices.get_unchecked(mid - 1) + prices.get_unchecked(mid)) / 2
+        };
+        Some(PriceData { price, timestamp: oldest })
+    }
+
+    // --- FEATURE 3: STORAGE UPKEEP ---
+
+    /// Permissionless: tops up the TTL of the oracle's configuration, plus the contract itself.
+    /// Reports are refreshed by their reporters.
+    pub fn bump(env: Env) {
+        env.storage().instance().extend_ttl(RECORD_TTL_THRESHOLD, RECORD_TTL);
+        for key in [DataKey::Config, DataKey::Base, DataKey::Assets, DataKey::Reporters] {
+            extend_record(&env, &key);
+        }
+    }
+}
+
+// ============================================================
+// 🛠️ INTERNAL HELPERS
+// ============================================================
+
+fn require_admin(env: &Env) -> Address {
+    let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+    admin.require_auth();
+    admin
+}
+
+fn validate_config(config: &OracleConfig) {
+    if config.max_age == 0 || config.min_reports == 0 { panic!("Invalid oracle config"); }
+}
+
+fn config(env: &Env) -> OracleConfig {
+    env.storage().persistent().get(&DataKey::Config).expect("Not initialized")
+}
+
+fn reporters(env: &Env) -> Vec<Address> {
+    env.storage().persistent().get(&DataKey::Reporters).unwrap_or(Vec::new(env))
+}
+
+/// Keeps `prices` ascending; reporter sets are small, so insertion is fine.
+fn insert_sorted(prices: &mut Vec<i128>, price: i128) {
+    let i = prices.iter().position(|p| p > price).unwrap_or(prices.len() as usize);
+    prices.insert(i as u32, price);
+}
+
+/// Writes a persistent record and tops up its TTL.
+fn store<V: IntoVal<Env, Val>>(env: &Env, key: &DataKey, value: &V) {
+    env.storage().persistent().set(key, value);
+    extend_record(env, key);
+}
+
+fn extend_record(env: &Env, key: &DataKey) {
+    if !env.storage().persistent().has(key) { return; }
+    env.storage().persistent().extend_ttl(key, RECORD_TTL_THRESHOLD, RECORD_TTL);
+}
+
+mod test;
@@ -0,0 +1,119 @@
+#![cfg(test)]
+
+use super::*;
+use soroban_sdk::{
+    symbol_short,
+    testutils::{Address as _, Ledger},
+    Address, Env,
+};
+
+const NOW: u64 = 1_700_000_000;
+const UNIT: i128 = 10_000_000; // 1 BZR at 7 decimals
+
+fn setup(env: &Env) -> OracleContractClient<'static> {
+    env.ledger().with_mut(|li| { li.timestamp = NOW; });
+    let config = OracleConfig { max_age: 3_600, min_reports: 2 };
+    let args = (Address::generate(env), Asset::Stellar(Address::generate(env)), config);
+    let client = OracleContractClient::new(env, &env.register(OracleContract, args));
+    client.set_asset(&php(), &true);
+    client
+}
+
+fn php() -> Asset {
+    Asset::Other(symbol_short!("PHP"))
+}
+
+fn reporters(env: &Env, client: &OracleContractClient, n: usize) -> [Address; 4] {
+    let all: [Address; 4] = core::array::from_fn(|_| Address::generate(env));
+    for reporter in all.iter().take(n) {
+        client.set_reporter(reporter, &true);
+    }
+    all
+}
+
+fn advance(env: &Env, seconds: u64) {
+    env.ledger().with_mut(|li| { li.timestamp += seconds; });
+}
+
+#[test]
+fn test_lastprice_is_median_of_reports() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let client = setup(&env);
+    let feeders = reporters(&env, &client, 4);
+
+    // 1 PHP is about 0.10 BZR; one feeder is badly off
+    client.submit_price(&feeders[0], &php(), &(UNIT / 10), &(NOW - 60));
+    client.submit_price(&feeders[1], &php(), &(UNIT * 12 / 100), &NOW);
+    client.submit_price(&feeders[2], &php(), &(UNIT * 50), &NOW);
+    assert_eq!(client.lastprice(&php()), Some(PriceData { price: UNIT * 12 / 100, timestamp: NOW - 60 }));
+
+    // An even count averages the middle two
+    client.submit_price(&feeders[3], &php(), &(UNIT * 8 / 100), &NOW);
+    assert_eq!(client.lastprice(&php()).unwrap().price, UNIT * 11 / 100);
+    assert_eq!(client.decimals(), 7);
+    assert_eq!(client.assets().len(), 1);
+}
+
+#[test]
+fn test_stale_reports_drop_out() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let client = setup(&env);
+    let feeders = reporters(&env, &client, 3);
+
+    client.submit_price(&feeders[0], &php(), &(UNIT / 10), &NOW);
+    client.submit_price(&feeders[1], &php(), &(UNIT / 10), &NOW);
+    advance(&env, 1_800);
+    client.submit_price(&feeders[2], &php(), &(UNIT / 5), &(NOW + 1_800));
+
+    // Two feeders go quiet: one fresh report is below the quorum
+    advance(&env, 1_801);
+    assert_eq!(client.lastprice(&php()), None);
+    client.submit_price(&feeders[0], &php(), &(UNIT / 5), &(NOW + 3_601));
+    assert_eq!(client.lastprice(&php()).unwrap().price, UNIT / 5);
+
+    // Removed reporters no longer count
+    client.set_reporter(&feeders[0], &false);
+    assert_eq!(client.lastprice(&php()), None);
+}
+
+#[test]
+fn test_submission_checks() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let client = setup(&env);
+    let feeders = reporters(&env, &client, 1);
+    let outsider = Address::generate(&env);
+
+    assert!(client.try_submit_price(&outsider, &php(), &UNIT, &NOW).is_err());
+    assert!(client.try_submit_price(&feeders[0], &Asset::Other(symbol_short!("USD")), &UNIT, &NOW).is_err());
+    assert!(client.try_submit_price(&feeders[0], &php(), &0, &NOW).is_err());
+    assert!(client.try_submit_price(&feeders[0], &php(), &UNIT, &(NOW + 1)).is_err());
+
+    client.submit_price(&feeders[0], &php(), &UNIT, &NOW);
+    assert_eq!(client.get_report(&php(), &feeders[0]), Some(PriceData { price: UNIT, timestamp: NOW }));
+}
+
+#[test]
+#[should_panic(expected = "Stale report")]
+fn test_old_report_rejected() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let client = setup(&env);
+    let feeders = reporters(&env, &client, 1);
+
+    client.submit_price(&feeders[0], &php(), &UNIT, &(NOW - 3_601));
+}
+
+#[test]
+#[should_panic(expected = "Stale report")]
+fn test_report_cannot_replace_newer_one() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let client = setup(&env);
+    let feeders = reporters(&env, &client, 1);
+
+    client.submit_price(&feeders[0], &php(), &UNIT, &NOW);
+    client.submit_price(&feeders[0], &php(), &(UNIT * 2), &(NOW - 10));
+}
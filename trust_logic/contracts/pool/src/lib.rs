@@ -0,0 +1,729 @@
+// 🏛️ PROJECT BAZAAR | LIQUIDITY POOL
+// Constant-product (x * y = k) pool for one pair of SEP-41 tokens, BZR/PI at launch.
+// Swap fees are recirculated to Node Guardians, weighted by bonded stake x attested uptime.
+// An attestation only counts for `max_attestation_age`; a guardian left unattested stops earning.
+// Whatever the guardians don't take stays in the reserves and accrues to liquidity providers.
+// LP shares are a SEP-41 token issued by the pool contract itself, so wallets and other
+// contracts can hold, approve and move them like any other asset.
+//
+// A pool can be gated, like the Seed Pool for "Module 01: Uptime Shield" holders: deposits and
+// swaps then check the caller's badges and Academy credentials on the Trust contract.
+
+#![no_std]
+use bazaar_types::{PoolGate, TrustClient, RECORD_TTL, RECORD_TTL_THRESHOLD};
+use soroban_sdk::{contract, contractevent, contractimpl, contracttype, token, Address, BytesN, Env, IntoVal, MuxedAddress, String, Val};
+
+// ============================================================
+// 🔧 PROTOCOL CONSTANTS
+// ============================================================
+
+const BPS: i128 = 10_000;
+const MAX_FEE_BPS: i128 = 1_000;
+/// Shares burned on the first deposit so the pool can never be fully drained and re-priced.
+const MINIMUM_LIQUIDITY: i128 = 1_000;
+/// Fixed-point scale of the per-weight reward accumulators.
+const REWARD_PRECISION: i128 = 1_000_000_000_000;
+const SHARE_NAME: &str = "Bazaar Pool Share";
+const SHARE_SYMBOL: &str = "BLP";
+
+// ============================================================
+// 📦 DATA STRUCTURES
+// ============================================================
+
+/// Launch values: 30 bps fee, all of it to guardians, a 0.1 Pi (1_000_000 stroops) minimum on the PI side,
+/// and attestations that lapse after 7 days (604_800 seconds).
+#[contracttype]
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct PoolConfig {
+    pub fee_bps: i128,
+    /// Smallest `amount_in` accepted when selling token A / token B.
+    pub min_swap_a: i128,
+    pub min_swap_b: i128,
+    /// Share of each swap fee routed to Node Guardians; the rest stays with liquidity providers.
+    pub guardian_cut_bps: i128,
+    /// Minimum stake, in token A, to register as a guardian.
+    pub min_guardian_bond: i128,
+    /// Seconds an uptime attestation counts for; past that the guardian stops earning until re-attested.
+    pub max_attestation_age: u64,
+}
+
+#[contracttype]
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Pool {
+    pub token_a: Address,
+    pub token_b: Address,
+    pub reserve_a: i128,
+    pub reserve_b: i128,
+    pub total_shares: i128,
+    /// Cumulative swap fees collected in each token, guardian cut included.
+    pub fees_a: i128,
+    pub fees_b: i128,
+}
+
+/// A bonded node operator. Rewards are tracked against the pot's accumulators
+/// (`debt_*` is the accumulator value already paid for, `owed_*` is settled but unclaimed).
+#[contracttype]
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Guardian {
+    pub stake: i128,
+    /// Last attested uptime, in bps. Zeroed once the attestation is older than `max_attestation_age`.
+    pub uptime_bps: i128,
+    pub attested_at: u64,
+    pub debt_a: i128,
+    pub debt_b: i128,
+    pub owed_a: i128,
+    pub owed_b: i128,
+}
+
+/// Sum of guardian weights (stake x uptime) and the fees earned per unit of weight so far.
+#[contracttype]
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct GuardianPot {
+    pub total_weight: i128,
+    pub acc_a: i128,
+    pub acc_b: i128,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct AllowanceValue {
+    pub amount: i128,
+    pub expiration_ledger: u32,
+}
+
+#[contracttype]
+pub enum DataKey {
+    Admin,
+    Config,
+    Pool,
+    Shares(Address),
+    Allowance(Address, Address),
+    Guardian(Address),
+    GuardianPot,
+    Attestor(Address),
+    Gate,
+}
+
+// --- SEP-41 events for LP shares ---
+
+#[contractevent(data_format = "single-value")]
+pub struct Transfer {
+    #[topic]
+    pub from: Address,
+    #[topic]
+    pub to: Address,
+    pub amount: i128,
+}
+
+#[contractevent(data_format = "vec")]
+pub struct Approve {
+    #[topic]
+    pub from: Address,
+    #[topic]
+    pub spender: Address,
+    pub amount: i128,
+    pub expiration_ledger: u32,
+}
+
+#[contractevent(data_format = "single-value")]
+pub struct Mint {
+    #[topic]
+    pub to: Address,
+    pub amount: i128,
+}
+
+#[contractevent(data_format = "single-value")]
+pub struct Burn {
+    #[topic]
+    pub from: Address,
+    pub amount: i128,
+}
+
+// ============================================================
+// ⚙️ THE CONTRACT
+// ============================================================
+#[contract]
+pub struct PoolContract;
+
+#[contractimpl]
+impl PoolContract {
+
+    pub fn __constructor(env: Env, admin: Address, token_a: Address, token_b: Address, config: PoolConfig) {
+        if token_a == token_b { panic!("Identical tokens"); }
+        validate_config(&config);
+        env.storage().instance().set(&DataKey::Admin, &admin);
+        store(&env, &DataKey::Config, &config);
+        let pool = Pool { token_a, token_b, reserve_a: 0, reserve_b: 0, total_shares: 0, fees_a: 0, fees_b: 0 };
+        store(&env, &DataKey::Pool, &pool);
+    }
+
+    pub fn get_admin(env: Env) -> Address {
+        env.storage().instance().get(&DataKey::Admin).unwrap()
+    }
+
+    pub fn set_admin(env: Env, new_admin: Address) {
+        require_admin(&env);
+        env.storage().instance().set(&DataKey::Admin, &new_admin);
+    }
+
+    pub fn set_config(env: Env, config: PoolConfig) {
+        require_admin(&env);
+        validate_config(&config);
+        store(&env, &DataKey::Config, &config);
+    }
+
+    pub fn get_config(env: Env) -> PoolConfig {
+        config(&env)
+    }
+
+    pub fn upgrade(env: Env, new_wasm_hash: BytesN<32>) {
+        require_admin(&env);
+        env.deployer().update_current_contract_wasm(new_wasm_hash);
+    }
+
+    /// Restricts `deposit` and `swap` to holders of the gate's badges or credentials. `None` opens
+    /// the pool. The DAO sets this through `GatePool` / `OpenPool` proposals once it holds the admin seat.
+    pub fn set_gate(env: Env, gate: Option<PoolGate>) {
+        require_admin(&env);
+        match gate {
+            Some(gate) => {
+                if gate.badges.is_empty() && gate.credentials.is_empty() { panic!("Empty gate"); }
+                store(&env, &DataKey::Gate, &gate);
+            }
+            None => env.storage().persistent().remove(&DataKey::Gate),
+        }
+    }
+
+    pub fn get_gate(env: Env) -> Option<PoolGate> {
+        env.storage().persistent().get(&DataKey::Gate)
+    }
+
+    /// True when `user` may deposit and swap here.
+    pub fn can_access(env: Env, user: Address) -> bool {
+        can_access(&env, &user)
+    }
+
+    // --- FEATURE 1: LIQUIDITY ---
+
+    /// Deposits at the current pool ratio, taking up to the desired amounts of each token.
+    /// The first deposit sets the price. Returns the LP shares minted to `user`.
+    pub fn deposit(env: Env, user: Address, desired_a: i128, min_a: i128, desired_b: i128, min_b: i128) -> i128 {
+        user.require_auth();
+        check_gate(&env, &user);
+        if desired_a <= 0 || desired_b <= 0 { panic!("Invalid amount"); }
+        let mut pool = pool(&env);
+
+        let (amount_a, amount_b) = if pool.total_shares == 0 {
+            (desired_a, desired_b)
+        } else {
+            let optimal_b = desired_a * pool.reserve_b / pool.reserve_a;
+            if optimal_b <= desired_b {
+                (desired_a, optimal_b)
+            } else {
+                (desired_b * pool.reserve_a / pool.reserve_b, desired_b)
+            }
+        };
+        if amount_a < min_a || amount_b < min_b { panic!("Slippage exceeded"); }
+
+        let shares = if pool.total_shares == 0 {
+            let initial = isqrt(amount_a * amount_b);
+            if initial <= MINIMUM_LIQUIDITY { panic!("Insufficient liquidity"); }
+            credit_shares(&env, &env.current_contract_address(), MINIMUM_LIQUIDITY);
+            pool.total_shares = MINIMUM_LIQUIDITY;
+            initial - MINIMUM_LIQUIDITY
+        } else {
+            let by_a = amount_a * pool.total_shares / pool.reserve_a;
+            let by_b = amount_b * pool.total_shares / pool.reserve_b;
+            by_a.min(by_b)
+        };
+        if shares <= 0 { panic!("Insufficient liquidity"); }
+
+        let here = env.current_contract_address();
+        token::Client::new(&env, &pool.token_a).transfer(&user, &here, &amount_a);
+        token::Client::new(&env, &pool.token_b).transfer(&user, &here, &amount_b);
+
+        pool.reserve_a += amount_a;
+        pool.reserve_b += amount_b;
+        pool.total_shares += shares;
+        save_pool(&env, &pool);
+        credit_shares(&env, &user, shares);
+        Mint { to: user, amount: shares }.publish(&env);
+        shares
+    }
+
+    /// Burns `shares` for their pro-rata slice of both reserves, fees included.
+    pub fn withdraw(env: Env, user: Address, shares: i128, min_a: i128, min_b: i128) -> (i128, i128) {
+        user.require_auth();
+        if shares <= 0 { panic!("Invalid amount"); }
+        let mut pool = pool(&env);
+
+        let amount_a = shares * pool.reserve_a / pool.total_shares;
+        let amount_b = shares * pool.reserve_b / pool.total_shares;
+        if amount_a < min_a || amount_b < min_b { panic!("Slippage exceeded"); }
+
+        debit_shares(&env, &user, shares);
+        pool.reserve_a -= amount_a;
+        pool.reserve_b -= amount_b;
+        pool.total_shares -= shares;
+        save_pool(&env, &pool);
+        Burn { from: user.clone(), amount: shares }.publish(&env);
+
+        let here = env.current_contract_address();
+        token::Client::new(&env, &pool.token_a).transfer(&here, &user, &amount_a);
+        token::Client::new(&env, &pool.token_b).transfer(&here, &user, &amount_b);
+        (amount_a, amount_b)
+    }
+
+    /// Same as the SEP-41 `balance`.
+    pub fn get_shares(env: Env, user: Address) -> i128 {
+        shares_of(&env, &user)
+    }
+
+    pub fn get_pool(env: Env) -> Pool {
+        pool(&env)
+    }
+
+    // --- FEATURE 2: SWAPS ---
+
+    /// Sells `amount_in` of `token_in` for the other token. `min_out` is the caller's slippage guard,
+    /// e.g. `get_quote` less 1.5%. Returns the amount received.
+    pub fn swap(env: Env, user: Address, token_in: Address, amount_in: i128, min_out: i128) -> i128 {
+        user.require_auth();
+        check_gate(&env, &user);
+        let mut pool = pool(&env);
+        let config = config(&env);
+        let sell_a = side_of(&pool, &token_in);
+
+        let min_swap = if sell_a { config.min_swap_a } else { config.min_swap_b };
+        if amount_in <= 0 || amount_in < min_swap { panic!("Below minimum swap"); }
+        let (reserve_in, reserve_out) = if sell_a { (pool.reserve_a, pool.reserve_b) } else { (pool.reserve_b, pool.reserve_a) };
+        let (amount_out, fee) = quote(amount_in, reserve_in, reserve_out, config.fee_bps);
+        if amount_out <= 0 { panic!("Insufficient liquidity"); }
+        if amount_out < min_out { panic!("Slippage exceeded"); }
+
+        let to_guardians = share_with_guardians(&env, fee * config.guardian_cut_bps / BPS, sell_a);
+        let token_out = if sell_a {
+            pool.reserve_a += amount_in - to_guardians;
+            pool.reserve_b -= amount_out;
+            pool.fees_a += fee;
+            pool.token_b.clone()
+        } else {
+            pool.reserve_b += amount_in - to_guardians;
+            pool.reserve_a -= amount_out;
+            pool.fees_b += fee;
+            pool.token_a.clone()
+        };
+        save_pool(&env, &pool);
+
+        let here = env.current_contract_address();
+        token::Client::new(&env, &token_in).transfer(&user, &here, &amount_in);
+        token::Client::new(&env, &token_out).transfer(&here, &user, &amount_out);
+        amount_out
+    }
+
+    /// What `swap` would pay out right now for `amount_in` of `token_in`, after the fee.
+    pub fn get_quote(env: Env, token_in: Address, amount_in: i128) -> i128 {
+        if amount_in <= 0 { panic!("Invalid amount"); }
+        let pool = pool(&env);
+        let (reserve_in, reserve_out) = if side_of(&pool, &token_in) {
+            (pool.reserve_a, pool.reserve_b)
+        } else {
+            (pool.reserve_b, pool.reserve_a)
+        };
+        quote(amount_in, reserve_in, reserve_out, config(&env).fee_bps).0
+    }
+
+    // --- FEATURE 3: NODE GUARDIANS ---
+
+    /// Bonds `stake` of token A. A new guardian earns nothing until an attestor reports its uptime.
+    pub fn register_guardian(env: Env, operator: Address, stake: i128) {
+        operator.require_auth();
+        let key = DataKey::Guardian(operator.clone());
+        if env.storage().persistent().has(&key) { panic!("Already a guardian"); }
+        if stake < config(&env).min_guardian_bond || stake <= 0 { panic!("Bond too small"); }
+
+        let pool = pool(&env);
+        token::Client::new(&env, &pool.token_a).transfer(&operator, env.current_contract_address(), &stake);
+        let pot = guardian_pot(&env);
+        let guardian = Guardian { stake, uptime_bps: 0, attested_at: 0, debt_a: 0, debt_b: 0, owed_a: 0, owed_b: 0 };
+        save_guardian(&env, &operator, guardian, &pot);
+    }
+
+    /// Returns the bond and pays out any unclaimed rewards.
+    pub fn unbond_guardian(env: Env, operator: Address) -> (i128, i128) {
+        operator.require_auth();
+        let mut pot = guardian_pot(&env);
+        let mut guardian = settled_guardian(&env, &operator, &mut pot);
+        pot.total_weight -= weight(&guardian);
+        store(&env, &DataKey::GuardianPot, &pot);
+        env.storage().persistent().remove(&DataKey::Guardian(operator.clone()));
+
+        let pool = pool(&env);
+        let here = env.current_contract_address();
+        token::Client::new(&env, &pool.token_a).transfer(&here, &operator, &guardian.stake);
+        guardian.stake = 0;
+        pay_rewards(&env, &pool, &operator, &guardian)
+    }
+
+    pub fn set_attestor(env: Env, attestor: Address, enabled: bool) {
+        require_admin(&env);
+        let key = DataKey::Attestor(attestor);
+        if enabled {
+            store(&env, &key, &true);
+        } else {
+            env.storage().persistent().remove(&key);
+        }
+    }
+
+    pub fn is_attestor(env: Env, who: Address) -> bool {
+        env.storage().persistent().has(&DataKey::Attestor(who))
+    }
+
+    /// Records a guardian's measured uptime; fees earned before this call keep the old weight.
+    pub fn attest_uptime(env: Env, attestor: Address, operator: Address, uptime_bps: i128) {
+        attestor.require_auth();
+        if !Self::is_attestor(env.clone(), attestor) { panic!("Not an attestor"); }
+        if !(0..=BPS).contains(&uptime_bps) { panic!("Invalid uptime"); }
+
+        let mut pot = guardian_pot(&env);
+        let mut guardian = settled_guardian(&env, &operator, &mut pot);
+        pot.total_weight -= weight(&guardian);
+        guardian.uptime_bps = uptime_bps;
+        guardian.attested_at = env.ledger().timestamp();
+        pot.total_weight += weight(&guardian);
+        store(&env, &DataKey::GuardianPot, &pot);
+        save_guardian(&env, &operator, guardian, &pot);
+    }
+
+    /// Permissionless: takes a guardian whose attestation has lapsed out of the pot, so its weight
+    /// stops diluting the guardians who keep attesting.
+    pub fn expire_guardian(env: Env, operator: Address) {
+        let guardian: Guardian = env.storage().persistent().get(&DataKey::Guardian(operator.clone())).expect("Not a guardian");
+        if !is_stale(&env, &guardian) { panic!("Attestation still fresh"); }
+        let mut pot = guardian_pot(&env);
+        let guardian = settled_guardian(&env, &operator, &mut pot);
+        store(&env, &DataKey::GuardianPot, &pot);
+        save_guardian(&env, &operator, guardian, &pot);
+    }
+
+    /// Pull-based: pays the guardian its share of fees in both tokens.
+    pub fn claim_rewards(env: Env, operator: Address) -> (i128, i128) {
+        operator.require_auth();
+        let mut pot = guardian_pot(&env);
+        let guardian = settled_guardian(&env, &operator, &mut pot);
+        store(&env, &DataKey::GuardianPot, &pot);
+        let pool = pool(&env);
+        let paid = pay_rewards(&env, &pool, &operator, &guardian);
+        let cleared = Guardian { owed_a: 0, owed_b: 0, ..guardian };
+        save_guardian(&env, &operator, cleared, &pot);
+        paid
+    }
+
+    pub fn get_guardian(env: Env, operator: Address) -> Option<Guardian> {
+        env.storage().persistent().get(&DataKey::Guardian(operator))
+    }
+
+    /// Rewards `operator` could claim right now, in (token A, token B).
+    pub fn get_pending_rewards(env: Env, operator: Address) -> (i128, i128) {
+        let guardian: Guardian = env.storage().persistent().get(&DataKey::Guardian(operator)).expect("Not a guardian");
+        if is_stale(&env, &guardian) { return (guardian.owed_a, guardian.owed_b); }
+        let (earned_a, earned_b) = earned(&guardian, &guardian_pot(&env));
+        (guardian.owed_a + earned_a, guardian.owed_b + earned_b)
+    }
+
+    pub fn get_guardian_pot(env: Env) -> GuardianPot {
+        guardian_pot(&env)
+    }
+
+    // --- FEATURE 4: STORAGE UPKEEP ---
+
+    /// Permissionless: tops up the TTL of `user`'s shares, guardian and attestor records and the pool state,
+    /// plus the contract itself.
+    pub fn bump(env: Env, user: Address) {
+        env.storage().instance().extend_ttl(RECORD_TTL_THRESHOLD, RECORD_TTL);
+        for key in [
+            DataKey::Shares(user.clone()),
+            DataKey::Guardian(user.clone()),
+            DataKey::Attestor(user),
+            DataKey::Pool,
+            DataKey::GuardianPot,
+            DataKey::Gate,
+            DataKey::Config,
+        ] {
+            extend_record(&env, &key);
+        }
+    }
+}
+
+// --- FEATURE 5: LP SHARES (SEP-41) ---
+
+#[contractimpl]
+impl token::TokenInterface for PoolContract {
+    fn allowance(env: Env, from: Address, spender: Address) -> i128 {
+        allowance_of(&env, &from, &spender).amount
+    }
+
+    fn approve(env: Env, from: Address, spender: Address, amount: i128, expiration_ledger: u32) {
+        from.require_auth();
+        if amount < 0 { panic!("Invalid amount"); }
+        if amount > 0 && expiration_ledger < env.ledger().sequence() { panic!("Expiration in the past"); }
+        let allowance = AllowanceValue { amount, expiration_ledger };
+        save_allowance(&env, &from, &spender, &allowance);
+        Approve { from, spender, amount, expiration_ledger }.publish(&env);
+    }
+
+    fn balance(env: Env, id: Address) -> i128 {
+        shares_of(&env, &id)
+    }
+
+    /// Positions change hands without leaving the pool.
+    fn transfer(env: Env, from: Address, to: MuxedAddress, amount: i128) {
+        from.require_auth();
+        move_shares(&env, from, to.address(), amount);
+    }
+
+    fn transfer_from(env: Env, spender: Address, from: Address, to: Address, amount: i128) {
+        spender.require_auth();
+        spend_allowance(&env, &from, &spender, amount);
+        move_shares(&env, from, to, amount);
+    }
+
+    /// Burning without withdrawing leaves the underlying tokens to the remaining providers.
+    fn burn(env: Env, from: Address, amount: i128) {
+        from.require_auth();
+        burn_shares(&env, from, amount);
+    }
+
+    fn burn_from(env: Env, spender: Address, from: Address, amount: i128) {
+        spender.require_auth();
+        spend_allowance(&env, &from, &spender, amount);
+        burn_shares(&env, from, amount);
+    }
+
+    /// Shares start at sqrt(a * b), so they sit halfway between the two tokens' scales.
+    fn decimals(env: Env) -> u32 {
+        let pool = pool(&env);
+        (token::Client::new(&env, &pool.token_a).decimals() + token::Client::new(&env, &pool.token_b).decimals()) / 2
+    }
+
+    fn name(env: Env) -> String {
+        String::from_str(&env, SHARE_NAME)
+    }
+
+    fn symbol(env: Env) -> String {
+        String::from_str(&env, SHARE_SYMBOL)
+    }
+}
+
+// ============================================================
+// 🛠️ INTERNAL HELPERS
+// ============================================================
+
+fn require_admin(env: &Env) -> Address {
+    let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+    admin.require_auth();
+    admin
+}
+
+fn validate_config(config: &PoolConfig) {
+    if config.fee_bps < 0 || config.fee_bps > MAX_FEE_BPS { panic!("Invalid fee"); }
+    if !(0..=BPS).contains(&config.guardian_cut_bps) { panic!("Invalid fee"); }
+    if config.min_swap_a < 0 || config.min_swap_b < 0 || config.min_guardian_bond < 0 { panic!("Invalid amount"); }
+    if config.max_attestation_age == 0 { panic!("Invalid attestation age"); }
+}
+
+fn can_access(env: &Env, user: &Address) -> bool {
+    let gate: PoolGate = match env.storage().persistent().get(&DataKey::Gate) {
+        Some(gate) => gate,
+        None => return true,
+    };
+    let trust = TrustClient::new(env, &gate.trust);
+    gate.badges.iter().any(|badge| trust.has_badge(user, &badge))
+        || gate.credentials.iter().any(|course| trust.has_credential(user, &course))
+}
+
+/// Withdrawals are never gated, so providers can always leave.
+fn check_gate(env: &Env, user: &Address) {
+    if !can_access(env, user) { panic!("Missing required credential"); }
+}
+
+fn config(env: &Env) -> PoolConfig {
+    env.storage().persistent().get(&DataKey::Config).expect("Not initialized")
+}
+
+fn pool(env: &Env) -> Pool {
+    env.storage().persistent().get(&DataKey::Pool).expect("Not initialized")
+}
+
+fn save_pool(env: &Env, pool: &Pool) {
+    store(env, &DataKey::Pool, pool);
+}
+
+/// True when `token` is token A, false for token B.
+fn side_of(pool: &Pool, token: &Address) -> bool {
+    if *token == pool.token_a { return true; }
+    if *token == pool.token_b { return false; }
+    panic!("Token not in pool");
+}
+
+/// (amount_out, fee) for selling `amount_in` into the given reserves. The fee is taken from the input.
+fn quote(amount_in: i128, reserve_in: i128, reserve_out: i128, fee_bps: i128) -> (i128, i128) {
+    if reserve_in == 0 || reserve_out == 0 { return (0, 0); }
+    let fee = amount_in * fee_bps / BPS;
+    let net_in = amount_in - fee;
+    (reserve_out * net_in / (reserve_in + net_in), fee)
+}
+
+fn guardian_pot(env: &Env) -> GuardianPot {
+    env.storage().persistent().get(&DataKey::GuardianPot).unwrap_or_default()
+}
+
+fn weight(guardian: &Guardian) -> i128 {
+    guardian.stake * guardian.uptime_bps
+}
+
+/// Credits `amount` of the fee to guardians and returns what they took.
+/// With no guardian weight yet, nothing is taken and the whole fee stays with liquidity providers.
+fn share_with_guardians(env: &Env, amount: i128, in_token_a: bool) -> i128 {
+    let mut pot = guardian_pot(env);
+    if amount <= 0 || pot.total_weight == 0 { return 0; }
+    let per_weight = amount * REWARD_PRECISION / pot.total_weight;
+    if per_weight == 0 { return 0; }
+    if in_token_a { pot.acc_a += per_weight } else { pot.acc_b += per_weight }
+    store(env, &DataKey::GuardianPot, &pot);
+    // Rounding dust stays in the reserves
+    per_weight * pot.total_weight / REWARD_PRECISION
+}
+
+/// True once a guardian's last attestation is older than the configured maximum age.
+fn is_stale(env: &Env, guardian: &Guardian) -> bool {
+    guardian.uptime_bps > 0 && env.ledger().timestamp() > guardian.attested_at + config(env).max_attestation_age
+}
+
+/// Fees accrued to the guardian's weight since its last settlement, in (token A, token B).
+fn earned(guardian: &Guardian, pot: &GuardianPot) -> (i128, i128) {
+    let weight = weight(guardian);
+    (weight * (pot.acc_a - guardian.debt_a) / REWARD_PRECISION, weight * (pot.acc_b - guardian.debt_b) / REWARD_PRECISION)
+}
+
+/// Loads a guardian with everything earned since its last settlement moved into `owed_*`.
+/// A stale guardian earns nothing: its weight leaves `pot` and the unsettled fees go back to
+/// the reserves, for liquidity providers. Callers persist `pot`.
+fn settled_guardian(env: &Env, operator: &Address, pot: &mut GuardianPot) -> Guardian {
+    let mut guardian: Guardian = env.storage().persistent().get(&DataKey::Guardian(operator.clone())).expect("Not a guardian");
+    let (earned_a, earned_b) = earned(&guardian, pot);
+    if is_stale(env, &guardian) {
+        pot.total_weight -= weight(&guardian);
+        guardian.uptime_bps = 0;
+        let mut pool = pool(env);
+        pool.reserve_a += earned_a;
+        pool.reserve_b += earned_b;
+        save_pool(env, &pool);
+    } else {
+        guardian.owed_a += earned_a;
+        guardian.owed_b += earned_b;
+    }
+    guardian.debt_a = pot.acc_a;
+    guardian.debt_b = pot.acc_b;
+    guardian
+}
+
+fn save_guardian(env: &Env, operator: &Address, mut guardian: Guardian, pot: &GuardianPot) {
+    guardian.debt_a = pot.acc_a;
+    guardian.debt_b = pot.acc_b;
+    let key = DataKey::Guardian(operator.clone());
+    store(env, &key, &guardian);
+}
+
+fn pay_rewards(env: &Env, pool: &Pool, operator: &Address, guardian: &Guardian) -> (i128, i128) {
+    let here = env.current_contract_address();
+    if guardian.owed_a > 0 {
+        token::Client::new(env, &pool.token_a).transfer(&here, operator, &guardian.owed_a);
+    }
+    if guardian.owed_b > 0 {
+        token::Client::new(env, &pool.token_b).transfer(&here, operator, &guardian.owed_b);
+    }
+    (guardian.owed_a, guardian.owed_b)
+}
+
+fn shares_of(env: &Env, user: &Address) -> i128 {
+    env.storage().persistent().get(&DataKey::Shares(user.clone())).unwrap_or(0)
+}
+
+fn credit_shares(env: &Env, user: &Address, amount: i128) {
+    let key = DataKey::Shares(user.clone());
+    store(env, &key, &(shares_of(env, user) + amount));
+}
+
+fn debit_shares(env: &Env, user: &Address, amount: i128) {
+    let balance = shares_of(env, user);
+    if balance < amount { panic!("Insufficient shares"); }
+    let key = DataKey::Shares(user.clone());
+    if balance == amount {
+        env.storage().persistent().remove(&key);
+    } else {
+        store(env, &key, &(balance - amount));
+    }
+}
+
+fn move_shares(env: &Env, from: Address, to: Address, amount: i128) {
+    if amount < 0 { panic!("Invalid amount"); }
+    debit_shares(env, &from, amount);
+    credit_shares(env, &to, amount);
+    Transfer { from, to, amount }.publish(env);
+}
+
+fn burn_shares(env: &Env, from: Address, amount: i128) {
+    if amount < 0 { panic!("Invalid amount"); }
+    debit_shares(env, &from, amount);
+    let mut pool = pool(env);
+    pool.total_shares -= amount;
+    save_pool(env, &pool);
+    Burn { from, amount }.publish(env);
+}
+
+/// Expired allowances read as zero.
+fn allowance_of(env: &Env, from: &Address, spender: &Address) -> AllowanceValue {
+    let allowance = env.storage().persistent().get::<_, AllowanceValue>(&DataKey::Allowance(from.clone(), spender.clone()));
+    match allowance {
+        Some(a) if a.expiration_ledger >= env.ledger().sequence() => a,
+        _ => AllowanceValue { amount: 0, expiration_ledger: 0 },
+    }
+}
+
+fn spend_allowance(env: &Env, from: &Address, spender: &Address, amount: i128) {
+    let mut allowance = allowance_of(env, from, spender);
+    if allowance.amount < amount { panic!("Insufficient allowance"); }
+    if amount == 0 { return; }
+    allowance.amount -= amount;
+    save_allowance(env, from, spender, &allowance);
+}
+
+fn save_allowance(env: &Env, from: &Address, spender: &Address, allowance: &AllowanceValue) {
+    let key = DataKey::Allowance(from.clone(), spender.clone());
+    store(env, &key, allowance);
+}
+
+/// Writes a persistent record and tops up its TTL.
+fn store<V: IntoVal<Env, Val>>(env: &Env, key: &DataKey, value: &V) {
+    env.storage().persistent().set(key, value);
+    extend_record(env, key);
+}
+
+fn extend_record(env: &Env, key: &DataKey) {
+    if !env.storage().persistent().has(key) { return; }
+    env.storage().persistent().extend_ttl(key, RECORD_TTL_THRESHOLD, RECORD_TTL);
+}
+
+fn isqrt(n: i128) -> i128 {
+    if n < 2 { return n; }
+    let mut x = n;
+    let mut y = (x + 1) / 2;
+    while y < x {
+        x = y;
+        y = (x + n / x) / 2;
+    }
+    x
+}
+
+mod test;
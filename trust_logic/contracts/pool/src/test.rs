@@ -0,0 +1,437 @@
+#![cfg(test)]
+
+use super::*;
+use bazaar_trust::{BadgeInfo, TrustContract, TrustContractClient};
+use bazaar_types::{Deployment, Role};
+use soroban_sdk::{
+    symbol_short,
+    testutils::{Address as _, Ledger},
+    token::{StellarAssetClient, TokenClient},
+    vec, Address, BytesN, Env, String,
+};
+
+const PI: i128 = 10_000_000; // Stroops
+
+/// Stand-in BZR (token A) and PI (token B) assets around a fresh pool.
+struct Bazaar {
+    pool: PoolContractClient<'static>,
+    bzr: Address,
+    pi: Address,
+}
+
+impl Bazaar {
+    fn mint(&self, env: &Env, to: &Address, bzr: i128, pi: i128) {
+        StellarAssetClient::new(env, &self.bzr).mock_all_auths().mint(to, &bzr);
+        StellarAssetClient::new(env, &self.pi).mock_all_auths().mint(to, &pi);
+    }
+
+    fn balances(&self, env: &Env, of: &Address) -> (i128, i128) {
+        (TokenClient::new(env, &self.bzr).balance(of), TokenClient::new(env, &self.pi).balance(of))
+    }
+}
+
+fn setup(env: &Env) -> Bazaar {
+    let issuer = Address::generate(env);
+    let bzr = env.register_stellar_asset_contract_v2(issuer.clone()).address();
+    let pi = env.register_stellar_asset_contract_v2(issuer).address();
+    let config = PoolConfig { fee_bps: 30, min_swap_a: 1, min_swap_b: PI / 10, guardian_cut_bps: 10_000, min_guardian_bond: 100, max_attestation_age: 604_800 };
+    let pool = PoolContractClient::new(env, &env.register(PoolContract, (Address::generate(env), &bzr, &pi, config)));
+    Bazaar { pool, bzr, pi }
+}
+
+/// The launch seed: 3,000 BZR against 300 Pi.
+fn seed(env: &Env, bz: &Bazaar) -> Address {
+    let provider = Address::generate(env);
+    bz.mint(env, &provider, 3_000, 300 * PI);
+    bz.pool.deposit(&provider, &3_000, &0, &(300 * PI), &0);
+    provider
+}
+
+// --- LIQUIDITY ---
+
+#[test]
+fn test_first_deposit_sets_price() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let bz = setup(&env);
+    let provider = seed(&env, &bz);
+
+    let pool = bz.pool.get_pool();
+    assert_eq!((pool.reserve_a, pool.reserve_b), (3_000, 300 * PI));
+    assert_eq!(pool.total_shares, 3_000_000);
+    assert_eq!(bz.pool.get_shares(&provider), 3_000_000 - MINIMUM_LIQUIDITY);
+    assert_eq!(bz.balances(&env, &bz.pool.address), (3_000, 300 * PI));
+}
+
+#[test]
+fn test_deposit_takes_pool_ratio() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let bz = setup(&env);
+    seed(&env, &bz);
+    let provider = Address::generate(&env);
+    bz.mint(&env, &provider, 300, 100 * PI);
+
+    // Only 30 Pi is needed to match 300 BZR; the rest stays with the provider
+    let shares = bz.pool.deposit(&provider, &300, &300, &(100 * PI), &(30 * PI));
+    assert_eq!(shares, 300_000);
+    assert_eq!(bz.balances(&env, &provider), (0, 70 * PI));
+}
+
+#[test]
+#[should_panic(expected = "Slippage exceeded")]
+fn test_deposit_respects_minimums() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let bz = setup(&env);
+    seed(&env, &bz);
+    let provider = Address::generate(&env);
+    bz.mint(&env, &provider, 300, 100 * PI);
+
+    bz.pool.deposit(&provider, &300, &300, &(100 * PI), &(31 * PI));
+}
+
+#[test]
+fn test_withdraw_returns_accrued_fees() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let bz = setup(&env);
+    let provider = seed(&env, &bz);
+    let trader = Address::generate(&env);
+    bz.mint(&env, &trader, 0, 50 * PI);
+
+    let bought = bz.pool.swap(&trader, &bz.pi, &(50 * PI), &0);
+    bz.pool.swap(&trader, &bz.bzr, &bought, &0);
+
+    // Round trips leave the fee behind, so k per share has grown
+    let pool = bz.pool.get_pool();
+    assert!(pool.fees_a > 0 && pool.fees_b > 0);
+    assert!(isqrt(pool.reserve_a * pool.reserve_b) > pool.total_shares);
+
+    let shares = bz.pool.get_shares(&provider);
+    let (out_a, out_b) = bz.pool.withdraw(&provider, &shares, &0, &0);
+    assert_eq!(bz.balances(&env, &provider), (out_a, out_b));
+    assert!(out_b > 300 * PI * shares / pool.total_shares);
+    assert_eq!(bz.pool.get_shares(&provider), 0);
+    assert_eq!(bz.pool.get_pool().total_shares, MINIMUM_LIQUIDITY);
+}
+
+#[test]
+fn test_shares_are_transferable() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let bz = setup(&env);
+    let provider = seed(&env, &bz);
+    let buyer = Address::generate(&env);
+
+    // Any SEP-41 client can move LP shares
+    let lp = TokenClient::new(&env, &bz.pool.address);
+    lp.transfer(&provider, &buyer, &1_000_000);
+    assert_eq!(lp.balance(&buyer), 1_000_000);
+    bz.pool.withdraw(&buyer, &1_000_000, &1_000, &(100 * PI));
+    assert_eq!(bz.balances(&env, &buyer), (1_000, 100 * PI));
+    assert!(bz.pool.try_withdraw(&buyer, &1, &0, &0).is_err());
+}
+
+#[test]
+fn test_shares_follow_sep41() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let bz = setup(&env);
+    let provider = seed(&env, &bz);
+    let vault = Address::generate(&env);
+    let lp = TokenClient::new(&env, &bz.pool.address);
+
+    assert_eq!(lp.symbol(), String::from_str(&env, "BLP"));
+    assert_eq!(lp.decimals(), 7);
+    lp.approve(&provider, &vault, &500_000, &(env.ledger().sequence() + 10));
+    lp.transfer_from(&vault, &provider, &vault, &400_000);
+    assert_eq!(lp.allowance(&provider, &vault), 100_000);
+    assert!(lp.try_transfer_from(&vault, &provider, &vault, &100_001).is_err());
+
+    // Burning shares without withdrawing leaves the reserves to everyone else
+    let before = bz.pool.get_pool();
+    lp.burn(&vault, &400_000);
+    let after = bz.pool.get_pool();
+    assert_eq!(after.total_shares, before.total_shares - 400_000);
+    assert_eq!((after.reserve_a, after.reserve_b), (before.reserve_a, before.reserve_b));
+}
+
+// --- SWAPS ---
+
+#[test]
+fn test_swap_matches_quote() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let bz = setup(&env);
+    seed(&env, &bz);
+    let trader = Address::generate(&env);
+    bz.mint(&env, &trader, 0, 10 * PI);
+
+    // 10 Pi less 0.3% against 3,000 BZR / 300 Pi
+    let quoted = bz.pool.get_quote(&bz.pi, &(10 * PI));
+    assert_eq!(quoted, 96);
+    assert_eq!(bz.pool.swap(&trader, &bz.pi, &(10 * PI), &quoted), quoted);
+    assert_eq!(bz.balances(&env, &trader), (96, 0));
+
+    let pool = bz.pool.get_pool();
+    assert_eq!((pool.reserve_a, pool.reserve_b), (2_904, 310 * PI));
+    assert_eq!((pool.fees_a, pool.fees_b), (0, 3 * PI / 100));
+}
+
+#[test]
+#[should_panic(expected = "Slippage exceeded")]
+fn test_swap_rejects_slippage() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let bz = setup(&env);
+    seed(&env, &bz);
+    let trader = Address::generate(&env);
+    bz.mint(&env, &trader, 0, 10 * PI);
+
+    let quoted = bz.pool.get_quote(&bz.pi, &(10 * PI));
+    bz.pool.swap(&trader, &bz.pi, &(10 * PI), &(quoted + 1));
+}
+
+#[test]
+#[should_panic(expected = "Below minimum swap")]
+fn test_swap_below_minimum_panics() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let bz = setup(&env);
+    seed(&env, &bz);
+    let trader = Address::generate(&env);
+    bz.mint(&env, &trader, 0, PI);
+
+    bz.pool.swap(&trader, &bz.pi, &(PI / 10 - 1), &0);
+}
+
+#[test]
+#[should_panic(expected = "Token not in pool")]
+fn test_quote_rejects_foreign_token() {
+    let env = Env::default();
+    let bz = setup(&env);
+
+    bz.pool.get_quote(&Address::generate(&env), &PI);
+}
+
+// --- NODE GUARDIANS ---
+
+fn guardian(env: &Env, bz: &Bazaar, attestor: &Address, stake: i128, uptime_bps: i128) -> Address {
+    let operator = Address::generate(env);
+    bz.mint(env, &operator, stake, 0);
+    bz.pool.register_guardian(&operator, &stake);
+    bz.pool.attest_uptime(attestor, &operator, &uptime_bps);
+    operator
+}
+
+#[test]
+fn test_fees_split_by_stake_and_uptime() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let bz = setup(&env);
+    seed(&env, &bz);
+    let attestor = Address::generate(&env);
+    bz.pool.set_attestor(&attestor, &true);
+    let steady = guardian(&env, &bz, &attestor, 100, 10_000);
+    let flaky = guardian(&env, &bz, &attestor, 300, 5_000); // 1.5x the weight of `steady`
+    let trader = Address::generate(&env);
+    bz.mint(&env, &trader, 0, 10 * PI);
+
+    bz.pool.swap(&trader, &bz.pi, &(10 * PI), &0);
+
+    // The whole 0.03 Pi fee leaves the reserves for the guardians
+    assert_eq!(bz.pool.get_pool().reserve_b, 310 * PI - 3 * PI / 100);
+    assert_eq!(bz.pool.get_pending_rewards(&steady), (0, 120_000));
+    assert_eq!(bz.pool.claim_rewards(&flaky), (0, 180_000));
+    assert_eq!(bz.balances(&env, &flaky), (0, 180_000));
+    assert_eq!(bz.pool.get_pending_rewards(&flaky), (0, 0));
+}
+
+#[test]
+fn test_uptime_changes_apply_to_later_fees() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let bz = setup(&env);
+    seed(&env, &bz);
+    let attestor = Address::generate(&env);
+    bz.pool.set_attestor(&attestor, &true);
+    let operator = Address::generate(&env);
+    bz.mint(&env, &operator, 100, 0);
+    let trader = Address::generate(&env);
+    bz.mint(&env, &trader, 0, 20 * PI);
+
+    // Not yet attested: no weight, so the fee stays with liquidity providers
+    bz.pool.register_guardian(&operator, &100);
+    bz.pool.swap(&trader, &bz.pi, &(10 * PI), &0);
+    assert_eq!(bz.pool.get_pool().reserve_b, 310 * PI);
+    assert_eq!(bz.pool.get_pending_rewards(&operator), (0, 0));
+
+    bz.pool.attest_uptime(&attestor, &operator, &7_500);
+    bz.pool.swap(&trader, &bz.pi, &(10 * PI), &0);
+    bz.pool.attest_uptime(&attestor, &operator, &0);
+    assert_eq!(bz.pool.get_pending_rewards(&operator), (0, 300_000));
+
+    // Unbonding hands back the stake along with what was earned
+    assert_eq!(bz.pool.unbond_guardian(&operator), (0, 300_000));
+    assert_eq!(bz.balances(&env, &operator), (100, 300_000));
+    assert_eq!(bz.pool.get_guardian(&operator), None);
+    assert_eq!(bz.pool.get_guardian_pot().total_weight, 0);
+}
+
+#[test]
+fn test_stale_guardian_stops_accruing() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let bz = setup(&env);
+    seed(&env, &bz);
+    let attestor = Address::generate(&env);
+    bz.pool.set_attestor(&attestor, &true);
+    let steady = guardian(&env, &bz, &attestor, 100, 10_000);
+    let lapsed = guardian(&env, &bz, &attestor, 100, 10_000);
+    let trader = Address::generate(&env);
+    bz.mint(&env, &trader, 0, 20 * PI);
+
+    bz.pool.swap(&trader, &bz.pi, &(10 * PI), &0);
+    assert_eq!(bz.pool.claim_rewards(&steady), (0, 150_000));
+    assert!(bz.pool.try_expire_guardian(&lapsed).is_err()); // Still fresh
+
+    // A week later only `steady` is re-attested; the unclaimed fees of `lapsed` go back to the reserves
+    env.ledger().with_mut(|li| { li.timestamp += 604_801; });
+    bz.pool.attest_uptime(&attestor, &steady, &10_000);
+    assert_eq!(bz.pool.get_pending_rewards(&lapsed), (0, 0));
+    let reserve_b = bz.pool.get_pool().reserve_b;
+    bz.pool.expire_guardian(&lapsed);
+    assert_eq!(bz.pool.get_pool().reserve_b, reserve_b + 150_000);
+    assert_eq!(bz.pool.get_guardian_pot().total_weight, 100 * 10_000);
+
+    // Later fees all go to the guardian that keeps attesting
+    bz.pool.swap(&trader, &bz.pi, &(10 * PI), &0);
+    assert_eq!(bz.pool.get_pending_rewards(&steady), (0, 300_000));
+    assert_eq!(bz.pool.get_pending_rewards(&lapsed), (0, 0));
+}
+
+#[test]
+#[should_panic(expected = "Bond too small")]
+fn test_guardian_bond_minimum() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let bz = setup(&env);
+    let operator = Address::generate(&env);
+    bz.mint(&env, &operator, 99, 0);
+
+    bz.pool.register_guardian(&operator, &99);
+}
+
+#[test]
+#[should_panic(expected = "Not an attestor")]
+fn test_guardian_cannot_self_attest() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let bz = setup(&env);
+    let operator = Address::generate(&env);
+    bz.mint(&env, &operator, 100, 0);
+    bz.pool.register_guardian(&operator, &100);
+
+    bz.pool.attest_uptime(&operator, &operator, &10_000);
+}
+
+// --- GATED POOLS ---
+
+/// A Trust contract with the Academy's Uptime Shield badge in its catalog and an issuer for it.
+fn academy(env: &Env) -> (TrustContractClient<'static>, Address) {
+    let deployment = Deployment {
+        trust: Address::generate(env),
+        vault: Address::generate(env),
+        token: Address::generate(env),
+        market: Address::generate(env),
+        governance: Address::generate(env),
+    };
+    let trust = TrustContractClient::new(env, &env.register_at(&deployment.trust, TrustContract, (Address::generate(env), &deployment)));
+    trust.set_badge(&symbol_short!("uptime01"), &BadgeInfo {
+        name: String::from_str(env, "Module 01: Uptime Shield"),
+        price: 0,
+        max_supply: 0,
+        minted: 0,
+        min_trust: 0,
+        soulbound: true,
+        burn: false,
+        academy: true,
+    });
+    let issuer = Address::generate(env);
+    trust.grant_role(&Role::AcademyIssuer, &issuer);
+    trust.grant_role(&Role::Instructor, &issuer);
+    (trust, issuer)
+}
+
+#[test]
+fn test_seed_pool_admits_badge_holders() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let bz = setup(&env);
+    let provider = seed(&env, &bz);
+    let (trust, issuer) = academy(&env);
+    let pioneer = Address::generate(&env);
+    let outsider = Address::generate(&env);
+    bz.mint(&env, &pioneer, 0, PI);
+    bz.mint(&env, &outsider, 0, PI);
+
+    bz.pool.set_gate(&Some(PoolGate {
+        trust: trust.address.clone(),
+        badges: vec![&env, symbol_short!("uptime01")],
+        credentials: vec![&env],
+    }));
+    trust.issue_badge(&issuer, &pioneer, &symbol_short!("uptime01"));
+
+    assert!(bz.pool.can_access(&pioneer));
+    assert!(!bz.pool.can_access(&outsider));
+    bz.pool.swap(&pioneer, &bz.pi, &PI, &0);
+    assert!(bz.pool.try_swap(&outsider, &bz.pi, &PI, &0).is_err());
+
+    // Providers who joined before the gate can still leave
+    let shares = bz.pool.get_shares(&provider);
+    bz.pool.withdraw(&provider, &shares, &0, &0);
+}
+
+#[test]
+fn test_gate_accepts_credentials_and_lifts() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let bz = setup(&env);
+    seed(&env, &bz);
+    let (trust, instructor) = academy(&env);
+    let graduate = Address::generate(&env);
+    let newcomer = Address::generate(&env);
+    bz.mint(&env, &graduate, 100, 10 * PI);
+    bz.mint(&env, &newcomer, 100, 10 * PI);
+
+    let course = symbol_short!("node101");
+    bz.pool.set_gate(&Some(PoolGate { trust: trust.address.clone(), badges: vec![&env], credentials: vec![&env, course.clone()] }));
+    trust.issue_credential(&instructor, &graduate, &course, &BytesN::from_array(&env, &[7; 32]));
+
+    bz.pool.deposit(&graduate, &100, &0, &(10 * PI), &0);
+    assert!(bz.pool.try_deposit(&newcomer, &100, &0, &(10 * PI), &0).is_err());
+
+    // Revoked credentials no longer count
+    trust.revoke_credential(&instructor, &graduate, &course);
+    assert!(!bz.pool.can_access(&graduate));
+
+    bz.pool.set_gate(&None);
+    assert_eq!(bz.pool.get_gate(), None);
+    bz.pool.deposit(&newcomer, &100, &0, &(10 * PI), &0);
+}
+
+#[test]
+#[should_panic(expected = "Missing required credential")]
+fn test_gated_swap_panics_without_credential() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let bz = setup(&env);
+    seed(&env, &bz);
+    let (trust, _) = academy(&env);
+    let outsider = Address::generate(&env);
+    bz.mint(&env, &outsider, 0, PI);
+
+    bz.pool.set_gate(&Some(PoolGate { trust: trust.address.clone(), badges: vec![&env, symbol_short!("uptime01")], credentials: vec![&env] }));
+    bz.pool.swap(&outsider, &bz.pi, &PI, &0);
+}
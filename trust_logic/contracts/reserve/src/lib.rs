@@ -0,0 +1,246 @@
+// 🏛️ PROJECT BAZAAR | BZR RESERVE
+// Holds the backing asset behind BZR (300 Pi at genesis, 1 BZR = 0.10 Pi). Holders redeem BZR
+// for their share of the reserve at NAV, within daily caps, and the BZR token asks this contract
+// before every mint so supply cannot outgrow the backing floor.
+
+#![no_std]
+use bazaar_types::{BzrClient, Deployment, RECORD_TTL, RECORD_TTL_THRESHOLD};
+use soroban_sdk::{contract, contractimpl, contracttype, token, Address, BytesN, Env, IntoVal, Val};
+
+// ============================================================
+// 🔧 PROTOCOL CONSTANTS
+// ============================================================
+
+const BPS: i128 = 10_000;
+const DAY: u64 = 86_400;
+
+// ============================================================
+// 📦 DATA STRUCTURES
+// ============================================================
+
+#[contracttype]
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ReserveConfig {
+    /// Backing units per BZR at full backing: 1_000_000 stroops (0.10 Pi) at genesis.
+    pub par: i128,
+    /// Lowest backing ratio, in bps of par, a mint may leave behind.
+    pub floor_bps: i128,
+    /// BZR redeemable per day across all holders, and per holder.
+    pub daily_cap: i128,
+    pub account_daily_cap: i128,
+}
+
+/// Published backing figures. `nav` is backing units per BZR; `ratio_bps` is the reserve
+/// against supply at par, and reads as `i128::MAX` while no BZR is outstanding.
+#[contracttype]
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Backing {
+    pub reserve: i128,
+    pub supply: i128,
+    pub nav: i128,
+    pub ratio_bps: i128,
+}
+
+/// BZR redeemed on `day` (days since the Unix epoch).
+#[contracttype]
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Redemptions {
+    pub day: u64,
+    pub amount: i128,
+}
+
+#[contracttype]
+pub enum DataKey {
+    Admin,
+    Deployment,
+    BackingAsset,
+    Config,
+    Redeemed,
+    RedeemedBy(Address),
+}
+
+// ============================================================
+// ⚙️ THE CONTRACT
+// ============================================================
+#[contract]
+pub struct ReserveContract;
+
+#[contractimpl]
+impl ReserveContract {
+
+    pub fn __constructor(env: Env, admin: Address, deployment: Deployment, backing_asset: Address, config: ReserveConfig) {
+        validate_config(&config);
+        env.storage().instance().set(&DataKey::Admin, &admin);
+        env.storage().instance().set(&DataKey::Deployment, &deployment);
+        store(&env, &DataKey::BackingAsset, &backing_asset);
+        store(&env, &DataKey::Config, &config);
+    }
+
+    pub fn get_admin(env: Env) -> Address {
+        env.storage().instance().get(&DataKey::Admin).unwrap()
+    }
+
+    pub fn set_admin(env: Env, new_admin: Address) {
+        require_admin(&env);
+        env.storage().instance().set(&DataKey::Admin, &new_admin);
+    }
+
+    pub fn set_config(env: Env, config: ReserveConfig) {
+        require_admin(&env);
+        validate_config(&config);
+        store(&env, &DataKey::Config, &config);
+    }
+
+    pub fn get_config(env: Env) -> ReserveConfig {
+        config(&env)
+    }
+
+    pub fn upgrade(env: Env, new_wasm_hash: BytesN<32>) {
+        require_admin(&env);
+        env.deployer().update_current_contract_wasm(new_wasm_hash);
+    }
+
+    // --- FEATURE 1: BACKING ---
+
+    /// Anyone can add to the reserve; there is no way to take backing out except by redeeming BZR.
+    pub fn deposit(env: Env, from: Address, amount: i128) {
+        from.require_auth();
+        if amount <= 0 { panic!("Invalid amount"); }
+        backing_asset(&env).transfer(&from, env.current_contract_address(), &amount);
+    }
+
+    pub fn get_backing_asset(env: Env) -> Address {
+        env.storage().persistent().get(&DataKey::BackingAsset).expect("Not initialized")
+    }
+
+    pub fn get_backing(env: Env) -> Backing {
+        let reserve = reserve_of(&env);
+        let supply = supply_of(&env);
+        Backing {
+            reserve,
+            supply,
+            nav: if supply > 0 { reserve / supply } else { 0 },
+            ratio_bps: ratio_bps(reserve, supply, config(&env).par),
+        }
+    }
+
+    /// Called by the BZR token before each mint; panics if `new_supply` would sit below the floor.
+    pub fn check_mint(env: Env, new_supply: i128) {
+        let config = config(&env);
+        if ratio_bps(reserve_of(&env), new_supply, config.par) < config.floor_bps { panic!("Backing below floor"); }
+    }
+
+    /// BZR that can still be minted on top of `supply` before `check_mint` would refuse, so
+    /// minters can size optional mints instead of reverting. Unlimited when the floor is zero.
+    pub fn get_headroom(env: Env, supply: i128) -> i128 {
+        let config = config(&env);
+        if config.floor_bps == 0 { return i128::MAX; }
+        let max_supply = reserve_of(&env) * BPS / (config.floor_bps * config.par);
+        (max_supply - supply).max(0)
+    }
+
+    // --- FEATURE 2: REDEMPTION ---
+
+    /// Burns `amount` BZR and pays its share of the reserve at the current NAV.
+    pub fn redeem(env: Env, holder: Address, amount: i128) -> i128 {
+        holder.require_auth();
+        if amount <= 0 { panic!("Invalid amount"); }
+        if amount > Self::get_redeemable(env.clone(), holder.clone()) { panic!("Daily redemption cap reached"); }
+
+        let supply = supply_of(&env);
+        if amount > supply { panic!("Insufficient BZR"); }
+        let payout = amount * reserve_of(&env) / supply;
+        if payout <= 0 { panic!("Nothing to redeem"); }
+
+        record_redemption(&env, DataKey::Redeemed, amount);
+        record_redemption(&env, DataKey::RedeemedBy(holder.clone()), amount);
+        token::Client::new(&env, &deployment(&env).token).burn(&holder, &amount);
+        backing_asset(&env).transfer(&env.current_contract_address(), &holder, &payout);
+        payout
+    }
+
+    /// BZR `holder` can still redeem today, under both the global and the per-holder cap.
+    pub fn get_redeemable(env: Env, holder: Address) -> i128 {
+        let config = config(&env);
+        let left = config.daily_cap - redeemed_today(&env, &DataKey::Redeemed);
+        let left_for_holder = config.account_daily_cap - redeemed_today(&env, &DataKey::RedeemedBy(holder));
+        left.min(left_for_holder).max(0)
+    }
+
+    // --- FEATURE 3: STORAGE UPKEEP ---
+
+    /// Permissionless: tops up the TTL of the reserve's configuration, plus the contract itself.
+    pub fn bump(env: Env) {
+        env.storage().instance().extend_ttl(RECORD_TTL_THRESHOLD, RECORD_TTL);
+        for key in [DataKey::BackingAsset, DataKey::Config] {
+            extend_record(&env, &key);
+        }
+    }
+}
+
+// ============================================================
+// 🛠️ INTERNAL HELPERS
+// ============================================================
+
+fn require_admin(env: &Env) -> Address {
+    let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+    admin.require_auth();
+    admin
+}
+
+fn deployment(env: &Env) -> Deployment {
+    env.storage().instance().get(&DataKey::Deployment).unwrap()
+}
+
+fn validate_config(config: &ReserveConfig) {
+    if config.par <= 0 || config.floor_bps < 0 { panic!("Invalid reserve config"); }
+    if config.daily_cap < 0 || config.account_daily_cap < 0 { panic!("Invalid reserve config"); }
+}
+
+fn config(env: &Env) -> ReserveConfig {
+    env.storage().persistent().get(&DataKey::Config).expect("Not initialized")
+}
+
+fn backing_asset(env: &Env) -> token::Client<'_> {
+    let asset: Address = env.storage().persistent().get(&DataKey::BackingAsset).expect("Not initialized");
+    token::Client::new(env, &asset)
+}
+
+fn reserve_of(env: &Env) -> i128 {
+    backing_asset(env).balance(&env.current_contract_address())
+}
+
+fn supply_of(env: &Env) -> i128 {
+    BzrClient::new(env, &deployment(env).token).total_supply()
+}
+
+fn ratio_bps(reserve: i128, supply: i128, par: i128) -> i128 {
+    if supply <= 0 { return i128::MAX; }
+    reserve * BPS / (supply * par)
+}
+
+fn redeemed_today(env: &Env, key: &DataKey) -> i128 {
+    let today = env.ledger().timestamp() / DAY;
+    match env.storage().persistent().get::<_, Redemptions>(key) {
+        Some(r) if r.day == today => r.amount,
+        _ => 0,
+    }
+}
+
+fn record_redemption(env: &Env, key: DataKey, amount: i128) {
+    let redemptions = Redemptions { day: env.ledger().timestamp() / DAY, amount: redeemed_today(env, &key) + amount };
+    store(env, &key, &redemptions);
+}
+
+/// Writes a persistent record and tops up its TTL.
+fn store<V: IntoVal<Env, Val>>(env: &Env, key: &DataKey, value: &V) {
+    env.storage().persistent().set(key, value);
+    extend_record(env, key);
+}
+
+fn extend_record(env: &Env, key: &DataKey) {
+    if !env.storage().persistent().has(key) { return; }
+    env.storage().persistent().extend_ttl(key, RECORD_TTL_THRESHOLD, RECORD_TTL);
+}
+
+mod test;
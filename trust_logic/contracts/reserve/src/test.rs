@@ -0,0 +1,143 @@
+#![cfg(test)]
+
+use super::*;
+use bazaar_token::{BzrToken, BzrTokenClient};
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    token::{StellarAssetClient, TokenClient},
+    Address, Env,
+};
+
+const PI: i128 = 10_000_000; // Stroops
+
+struct Bazaar {
+    admin: Address,
+    reserve: ReserveContractClient<'static>,
+    token: BzrTokenClient<'static>,
+    pi: Address,
+}
+
+impl Bazaar {
+    fn mint(&self, to: &Address, amount: &i128) {
+        self.token.mint(&self.admin, to, amount);
+    }
+
+    fn fund(&self, env: &Env, amount: i128) {
+        let donor = Address::generate(env);
+        StellarAssetClient::new(env, &self.pi).mock_all_auths().mint(&donor, &amount);
+        self.reserve.deposit(&donor, &amount);
+    }
+}
+
+/// The genesis reserve: 300 Pi behind 3,000 BZR, fully backed.
+fn setup(env: &Env) -> Bazaar {
+    let admin = Address::generate(env);
+    let token = BzrTokenClient::new(env, &env.register(BzrToken, (&admin,)));
+    let pi = env.register_stellar_asset_contract_v2(Address::generate(env)).address();
+    let deployment = Deployment {
+        trust: Address::generate(env),
+        vault: Address::generate(env),
+        token: token.address.clone(),
+        market: Address::generate(env),
+        governance: Address::generate(env),
+    };
+    let config = ReserveConfig { par: PI / 10, floor_bps: 10_000, daily_cap: 500, account_daily_cap: 200 };
+    let reserve = ReserveContractClient::new(env, &env.register(ReserveContract, (&admin, &deployment, &pi, config)));
+    token.mock_all_auths().set_reserve(&Some(reserve.address.clone()));
+    let bz = Bazaar { admin, reserve, token, pi };
+    bz.fund(env, 300 * PI);
+    bz
+}
+
+// --- BACKING ---
+
+#[test]
+fn test_genesis_mint_is_fully_backed() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let bz = setup(&env);
+    let treasury = Address::generate(&env);
+
+    bz.mint(&treasury, &3_000);
+    assert_eq!(bz.reserve.get_backing(), Backing { reserve: 300 * PI, supply: 3_000, nav: PI / 10, ratio_bps: 10_000 });
+
+    // One more BZR needs another 0.10 Pi behind it
+    assert_eq!(bz.token.mintable(), 0);
+    assert!(bz.token.try_mint(&bz.admin, &treasury, &1).is_err());
+    bz.fund(&env, PI / 10);
+    assert_eq!(bz.token.mintable(), 1);
+    bz.mint(&treasury, &1);
+    assert_eq!(bz.token.total_supply(), 3_001);
+}
+
+#[test]
+#[should_panic(expected = "Backing below floor")]
+fn test_mint_below_floor_panics() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let bz = setup(&env);
+
+    bz.mint(&Address::generate(&env), &3_001);
+}
+
+#[test]
+fn test_floor_can_allow_fractional_backing() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let bz = setup(&env);
+    let treasury = Address::generate(&env);
+
+    bz.reserve.set_config(&ReserveConfig { floor_bps: 8_000, ..bz.reserve.get_config() });
+    assert_eq!(bz.reserve.get_headroom(&0), 3_750);
+    bz.mint(&treasury, &3_750);
+    assert_eq!(bz.reserve.get_backing().ratio_bps, 8_000);
+    assert!(bz.token.try_mint(&bz.admin, &treasury, &1).is_err());
+
+    // Without a reserve the token mints freely
+    bz.token.set_reserve(&None);
+    bz.mint(&treasury, &1);
+}
+
+// --- REDEMPTION ---
+
+#[test]
+fn test_redeem_pays_nav() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let bz = setup(&env);
+    let holder = Address::generate(&env);
+    bz.mint(&holder, &2_900);
+    bz.mint(&Address::generate(&env), &100);
+
+    // A surplus lifts NAV above par; redeemers share it
+    bz.fund(&env, 30 * PI);
+    assert_eq!(bz.reserve.get_backing().nav, PI * 11 / 100);
+    assert_eq!(bz.reserve.redeem(&holder, &100), 11 * PI);
+
+    assert_eq!(TokenClient::new(&env, &bz.pi).balance(&holder), 11 * PI);
+    assert_eq!(bz.token.balance(&holder), 2_800);
+    assert_eq!(bz.reserve.get_backing(), Backing { reserve: 319 * PI, supply: 2_900, nav: PI * 11 / 100, ratio_bps: 11_000 });
+}
+
+#[test]
+fn test_daily_redemption_caps() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().with_mut(|li| { li.timestamp = 1_700_000_000; });
+    let bz = setup(&env);
+    let holders: [Address; 3] = core::array::from_fn(|_| Address::generate(&env));
+    for holder in &holders { bz.mint(holder, &1_000); }
+
+    bz.reserve.redeem(&holders[0], &200);
+    assert_eq!(bz.reserve.get_redeemable(&holders[0]), 0);
+    assert!(bz.reserve.try_redeem(&holders[0], &1).is_err());
+
+    // The global cap binds before the per-holder one
+    bz.reserve.redeem(&holders[1], &200);
+    assert_eq!(bz.reserve.get_redeemable(&holders[2]), 100);
+    assert!(bz.reserve.try_redeem(&holders[2], &101).is_err());
+
+    env.ledger().with_mut(|li| { li.timestamp += 86_400; });
+    bz.reserve.redeem(&holders[2], &200);
+    assert_eq!(bz.reserve.get_redeemable(&holders[0]), 200);
+}
@@ -0,0 +1,413 @@
+// 🏛️ PROJECT BAZAAR | BZR TOKEN
+// SEP-41 token for the Bazaar. Minting is limited to the admin and approved minters, and every
+// balance change writes a vote checkpoint so the DAO can tally against past ledgers.
+// Once a reserve is set, mints that would leave BZR under-backed are refused.
+//
+// The deployer holds the admin seat only for genesis: once the founder and team allocations are
+// locked in Vesting grants, `set_admin` hands it to Governance, and from then on new BZR can only
+// come from minters the DAO approves through `SetMinter` proposals.
+
+#![no_std]
+use bazaar_types::{ReserveClient, RECORD_TTL, RECORD_TTL_THRESHOLD};
+use soroban_sdk::{contract, contractevent, contractimpl, contracttype, token, Address, BytesN, Env, IntoVal, MuxedAddress, String, Val};
+
+// ============================================================
+// 🔧 PROTOCOL CONSTANTS
+// ============================================================
+
+const DECIMALS: u32 = 0; // BZR has always been counted in whole units
+const NAME: &str = "Bazaar Token";
+const SYMBOL: &str = "BZR";
+
+// ============================================================
+// 📦 DATA STRUCTURES
+// ============================================================
+
+#[contracttype]
+#[derive(Clone)]
+pub struct AllowanceValue {
+    pub amount: i128,
+    pub expiration_ledger: u32,
+}
+
+/// Voting power held by an account from `ledger` onward.
+#[contracttype]
+#[derive(Clone)]
+pub struct Checkpoint {
+    pub ledger: u32,
+    pub votes: i128,
+}
+
+#[contracttype]
+pub enum DataKey {
+    Admin,
+    Minter(Address),
+    Balance(Address),
+    Allowance(Address, Address),
+    Delegate(Address),
+    /// One entry per checkpoint, so a transfer only touches the latest one.
+    Checkpoints(Address, u32),
+    CheckpointCount(Address),
+    Supply,
+    Reserve,
+}
+
+// --- SEP-41 events ---
+
+#[contractevent(data_format = "single-value")]
+pub struct Transfer {
+    #[topic]
+    pub from: Address,
+    #[topic]
+    pub to: Address,
+    pub amount: i128,
+}
+
+#[contractevent(data_format = "vec")]
+pub struct Approve {
+    #[topic]
+    pub from: Address,
+    #[topic]
+    pub spender: Address,
+    pub amount: i128,
+    pub expiration_ledger: u32,
+}
+
+#[contractevent(data_format = "single-value")]
+pub struct Mint {
+    #[topic]
+    pub to: Address,
+    pub amount: i128,
+}
+
+#[contractevent(data_format = "single-value")]
+pub struct Burn {
+    #[topic]
+    pub from: Address,
+    pub amount: i128,
+}
+
+// ============================================================
+// ⚙️ THE CONTRACT
+// ============================================================
+#[contract]
+pub struct BzrToken;
+
+#[contractimpl]
+impl BzrToken {
+
+    // --- FEATURE 1: ADMIN & MINTING ---
+
+    pub fn __constructor(env: Env, admin: Address) {
+        env.storage().instance().set(&DataKey::Admin, &admin);
+    }
+
+    pub fn get_admin(env: Env) -> Address {
+        env.storage().instance().get(&DataKey::Admin).unwrap()
+    }
+
+    /// Hands the admin seat, and with it all mint authority, to the Governance contract.
+    pub fn set_admin(env: Env, new_admin: Address) {
+        require_admin(&env);
+        env.storage().instance().set(&DataKey::Admin, &new_admin);
+    }
+
+    /// Approved minters issue BZR on their own authority, e.g. the Trust contract for referral rewards.
+    pub fn set_minter(env: Env, minter: Address, enabled: bool) {
+        require_admin(&env);
+        let key = DataKey::Minter(minter);
+        if enabled {
+            store(&env, &key, &true);
+        } else {
+            env.storage().persistent().remove(&key);
+        }
+    }
+
+    pub fn is_minter(env: Env, who: Address) -> bool {
+        env.storage().persistent().has(&DataKey::Minter(who))
+    }
+
+    /// `minter` must be the admin or an approved minter.
+    pub fn mint(env: Env, minter: Address, to: Address, amount: i128) {
+        minter.require_auth();
+        if minter != Self::get_admin(env.clone()) && !Self::is_minter(env.clone(), minter) { panic!("Not a minter"); }
+        if amount <= 0 { panic!("Invalid amount"); }
+        let supply = supply_of(&env) + amount;
+        if let Some(reserve) = Self::get_reserve(env.clone()) {
+            ReserveClient::new(&env, &reserve).check_mint(&supply);
+        }
+        set_supply(&env, supply);
+        credit(&env, &to, amount);
+        Mint { to, amount }.publish(&env);
+    }
+
+    /// BZR minted less BZR burned, counted since the token was deployed with supply tracking.
+    pub fn total_supply(env: Env) -> i128 {
+        supply_of(&env)
+    }
+
+    /// Every mint is checked against this reserve's backing floor. `None` turns the check off.
+    pub fn set_reserve(env: Env, reserve: Option<Address>) {
+        require_admin(&env);
+        match reserve {
+            Some(reserve) => store(&env, &DataKey::Reserve, &reserve),
+            None => env.storage().persistent().remove(&DataKey::Reserve),
+        }
+    }
+
+    pub fn get_reserve(env: Env) -> Option<Address> {
+        env.storage().persistent().get(&DataKey::Reserve)
+    }
+
+    /// BZR that can be minted right now without breaching the reserve floor.
+    pub fn mintable(env: Env) -> i128 {
+        match Self::get_reserve(env.clone()) {
+            Some(reserve) => ReserveClient::new(&env, &reserve).get_headroom(&supply_of(&env)),
+            None => i128::MAX,
+        }
+    }
+
+    pub fn upgrade(env: Env, new_wasm_hash: BytesN<32>) {
+        require_admin(&env);
+        env.deployer().update_current_contract_wasm(new_wasm_hash);
+    }
+
+    // --- FEATURE 2: VOTE DELEGATION & SNAPSHOTS ---
+
+    /// Hands the delegator's full BZR weight to `delegatee`. Accounts self-delegate by default.
+    pub fn delegate(env: Env, delegator: Address, delegatee: Address) {
+        delegator.require_auth();
+        let current = delegate_of(&env, &delegator);
+        if current == delegatee { return; }
+
+        let balance = balance_of(&env, &delegator);
+        move_votes(&env, &current, -balance);
+        move_votes(&env, &delegatee, balance);
+        store(&env, &DataKey::Delegate(delegator), &delegatee);
+    }
+
+    pub fn get_delegate(env: Env, account: Address) -> Address {
+        delegate_of(&env, &account)
+    }
+
+    pub fn get_votes(env: Env, account: Address) -> i128 {
+        votes_at(&env, &account, env.ledger().sequence())
+    }
+
+    /// Voting power at the end of a ledger that has already closed.
+    pub fn get_past_votes(env: Env, account: Address, ledger: u32) -> i128 {
+        if ledger >= env.ledger().sequence() { panic!("Ledger not yet finalized"); }
+        votes_at(&env, &account, ledger)
+    }
+
+    // --- FEATURE 3: STORAGE UPKEEP ---
+
+    /// Permissionless: tops up the TTL of `account`'s balance, delegation, minter approval and
+    /// every vote checkpoint it has, so past votes stay readable, plus supply and the contract itself.
+    pub fn bump(env: Env, account: Address) {
+        env.storage().instance().extend_ttl(RECORD_TTL_THRESHOLD, RECORD_TTL);
+        for key in [
+            DataKey::Balance(account.clone()),
+            DataKey::Delegate(account.clone()),
+            DataKey::Minter(account.clone()),
+            DataKey::CheckpointCount(account.clone()),
+            DataKey::Supply,
+            DataKey::Reserve,
+        ] {
+            extend_record(&env, &key);
+        }
+        for index in 0..checkpoint_count(&env, &account) {
+            extend_record(&env, &DataKey::Checkpoints(account.clone(), index));
+        }
+    }
+
+    /// Tops up an allowance that has to outlive the usual record TTL.
+    pub fn bump_allowance(env: Env, from: Address, spender: Address) {
+        extend_record(&env, &DataKey::Allowance(from, spender));
+    }
+}
+
+// --- FEATURE 4: SEP-41 INTERFACE ---
+
+#[contractimpl]
+impl token::TokenInterface for BzrToken {
+    fn allowance(env: Env, from: Address, spender: Address) -> i128 {
+        allowance_of(&env, &from, &spender).amount
+    }
+
+    fn approve(env: Env, from: Address, spender: Address, amount: i128, expiration_ledger: u32) {
+        from.require_auth();
+        if amount < 0 { panic!("Invalid amount"); }
+        if amount > 0 && expiration_ledger < env.ledger().sequence() { panic!("Expiration in the past"); }
+        let allowance = AllowanceValue { amount, expiration_ledger };
+        save_allowance(&env, &from, &spender, &allowance);
+        Approve { from, spender, amount, expiration_ledger }.publish(&env);
+    }
+
+    fn balance(env: Env, id: Address) -> i128 {
+        balance_of(&env, &id)
+    }
+
+    fn transfer(env: Env, from: Address, to: MuxedAddress, amount: i128) {
+        from.require_auth();
+        move_balance(&env, from, to.address(), amount);
+    }
+
+    fn transfer_from(env: Env, spender: Address, from: Address, to: Address, amount: i128) {
+        spender.require_auth();
+        spend_allowance(&env, &from, &spender, amount);
+        move_balance(&env, from, to, amount);
+    }
+
+    fn burn(env: Env, from: Address, amount: i128) {
+        from.require_auth();
+        if amount < 0 { panic!("Invalid amount"); }
+        debit(&env, &from, amount);
+        set_supply(&env, supply_of(&env) - amount);
+        Burn { from, amount }.publish(&env);
+    }
+
+    fn burn_from(env: Env, spender: Address, from: Address, amount: i128) {
+        spender.require_auth();
+        if amount < 0 { panic!("Invalid amount"); }
+        spend_allowance(&env, &from, &spender, amount);
+        debit(&env, &from, amount);
+        set_supply(&env, supply_of(&env) - amount);
+        Burn { from, amount }.publish(&env);
+    }
+
+    fn decimals(_env: Env) -> u32 {
+        DECIMALS
+    }
+
+    fn name(env: Env) -> String {
+        String::from_str(&env, NAME)
+    }
+
+    fn symbol(env: Env) -> String {
+        String::from_str(&env, SYMBOL)
+    }
+}
+
+// ============================================================
+// 🛠️ INTERNAL HELPERS
+// ============================================================
+
+fn require_admin(env: &Env) -> Address {
+    let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+    admin.require_auth();
+    admin
+}
+
+/// Writes a persistent record and tops up its TTL.
+fn store<V: IntoVal<Env, Val>>(env: &Env, key: &DataKey, value: &V) {
+    env.storage().persistent().set(key, value);
+    extend_record(env, key);
+}
+
+fn extend_record(env: &Env, key: &DataKey) {
+    if !env.storage().persistent().has(key) { return; }
+    env.storage().persistent().extend_ttl(key, RECORD_TTL_THRESHOLD, RECORD_TTL);
+}
+
+fn supply_of(env: &Env) -> i128 {
+    env.storage().persistent().get(&DataKey::Supply).unwrap_or(0)
+}
+
+/// Floored at zero, since BZR minted before supply tracking can still be burned.
+fn set_supply(env: &Env, supply: i128) {
+    store(env, &DataKey::Supply, &supply.max(0));
+}
+
+fn balance_of(env: &Env, id: &Address) -> i128 {
+    env.storage().persistent().get(&DataKey::Balance(id.clone())).unwrap_or(0)
+}
+
+fn set_balance(env: &Env, id: &Address, balance: i128) {
+    store(env, &DataKey::Balance(id.clone()), &balance);
+}
+
+// All balance changes go through `credit`/`debit` so vote checkpoints stay in sync.
+fn credit(env: &Env, id: &Address, amount: i128) {
+    set_balance(env, id, balance_of(env, id) + amount);
+    move_votes(env, &delegate_of(env, id), amount);
+}
+
+fn debit(env: &Env, id: &Address, amount: i128) {
+    let balance = balance_of(env, id);
+    if balance < amount { panic!("Insufficient BZR"); }
+    set_balance(env, id, balance - amount);
+    move_votes(env, &delegate_of(env, id), -amount);
+}
+
+fn move_balance(env: &Env, from: Address, to: Address, amount: i128) {
+    if amount < 0 { panic!("Invalid amount"); }
+    debit(env, &from, amount);
+    credit(env, &to, amount);
+    Transfer { from, to, amount }.publish(env);
+}
+
+/// Expired allowances read as zero.
+fn allowance_of(env: &Env, from: &Address, spender: &Address) -> AllowanceValue {
+    let allowance = env.storage().persistent().get::<_, AllowanceValue>(&DataKey::Allowance(from.clone(), spender.clone()));
+    match allowance {
+        Some(a) if a.expiration_ledger >= env.ledger().sequence() => a,
+        _ => AllowanceValue { amount: 0, expiration_ledger: 0 },
+    }
+}
+
+fn spend_allowance(env: &Env, from: &Address, spender: &Address, amount: i128) {
+    let mut allowance = allowance_of(env, from, spender);
+    if allowance.amount < amount { panic!("Insufficient allowance"); }
+    if amount == 0 { return; }
+    allowance.amount -= amount;
+    save_allowance(env, from, spender, &allowance);
+}
+
+fn save_allowance(env: &Env, from: &Address, spender: &Address, allowance: &AllowanceValue) {
+    store(env, &DataKey::Allowance(from.clone(), spender.clone()), allowance);
+}
+
+fn delegate_of(env: &Env, account: &Address) -> Address {
+    env.storage().persistent().get(&DataKey::Delegate(account.clone())).unwrap_or(account.clone())
+}
+
+fn checkpoint_count(env: &Env, account: &Address) -> u32 {
+    env.storage().persistent().get(&DataKey::CheckpointCount(account.clone())).unwrap_or(0)
+}
+
+fn checkpoint(env: &Env, account: &Address, index: u32) -> Checkpoint {
+    env.storage().persistent().get(&DataKey::Checkpoints(account.clone(), index)).unwrap()
+}
+
+/// Writes a checkpoint for the current ledger, folding repeat changes within one ledger together.
+fn move_votes(env: &Env, account: &Address, delta: i128) {
+    if delta == 0 { return; }
+    let ledger = env.ledger().sequence();
+    let count = checkpoint_count(env, account);
+    let last = if count > 0 { checkpoint(env, account, count - 1) } else { Checkpoint { ledger, votes: 0 } };
+    let index = if count > 0 && last.ledger == ledger { count - 1 } else { count };
+
+    store(env, &DataKey::Checkpoints(account.clone(), index), &Checkpoint { ledger, votes: last.votes + delta });
+    if index == count {
+        store(env, &DataKey::CheckpointCount(account.clone()), &(count + 1));
+    }
+}
+
+/// Binary search for the last checkpoint at or before `ledger`. Reads at or after the latest
+/// checkpoint, the common case for live proposals, touch a single entry.
+fn votes_at(env: &Env, account: &Address, ledger: u32) -> i128 {
+    let count = checkpoint_count(env, account);
+    if count == 0 { return 0; }
+    let last = checkpoint(env, account, count - 1);
+    if last.ledger <= ledger { return last.votes; }
+
+    let (mut low, mut high) = (0u32, count - 1);
+    while low < high {
+        let mid = (low + high) / 2;
+        if checkpoint(env, account, mid).ledger > ledger { high = mid; } else { low = mid + 1; }
+    }
+    if low == 0 { 0 } else { checkpoint(env, account, low - 1).votes }
+}
+
+mod test;
@@ -0,0 +1,246 @@
+#![cfg(test)]
+
+use super::*;
+use soroban_sdk::{
+    testutils::{storage::Persistent as _, Address as _, Ledger},
+    Address, Env,
+};
+
+fn setup(env: &Env) -> (BzrTokenClient<'static>, Address) {
+    let admin = Address::generate(env);
+    let client = BzrTokenClient::new(env, &env.register(BzrToken, (&admin,)));
+    (client, admin)
+}
+
+fn next_ledger(env: &Env) {
+    env.ledger().with_mut(|li| { li.sequence_number += 1; });
+}
+
+#[test]
+fn test_transfer_bzr() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin) = setup(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+
+    client.mint(&admin, &user1, &10);
+    client.transfer(&user1, &user2, &3);
+
+    assert_eq!(client.balance(&user1), 7);
+    assert_eq!(client.balance(&user2), 3);
+}
+
+#[test]
+fn test_metadata() {
+    let env = Env::default();
+    let (client, _) = setup(&env);
+
+    assert_eq!(client.decimals(), 0);
+    assert_eq!(client.name(), String::from_str(&env, "Bazaar Token"));
+    assert_eq!(client.symbol(), String::from_str(&env, "BZR"));
+}
+
+#[test]
+fn test_approved_minter_can_mint() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin) = setup(&env);
+    let minter = Address::generate(&env);
+    let user = Address::generate(&env);
+
+    client.set_minter(&minter, &true);
+    assert!(client.is_minter(&minter));
+    client.mint(&minter, &user, &25);
+    assert_eq!(client.balance(&user), 25);
+
+    client.set_minter(&minter, &false);
+    assert!(!client.is_minter(&minter));
+    assert!(client.try_mint(&minter, &user, &25).is_err());
+    assert_eq!(client.get_admin(), admin);
+}
+
+#[test]
+#[should_panic(expected = "Not a minter")]
+fn test_stranger_cannot_mint() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _) = setup(&env);
+    let stranger = Address::generate(&env);
+
+    client.mint(&stranger, &stranger, &1_000);
+}
+
+#[test]
+fn test_allowance_and_transfer_from() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin) = setup(&env);
+    let owner = Address::generate(&env);
+    let spender = Address::generate(&env);
+    let payee = Address::generate(&env);
+
+    client.mint(&admin, &owner, &100);
+    let expiration = env.ledger().sequence() + 10;
+    client.approve(&owner, &spender, &60, &expiration);
+    client.transfer_from(&spender, &owner, &payee, &40);
+
+    assert_eq!(client.allowance(&owner, &spender), 20);
+    assert_eq!(client.balance(&owner), 60);
+    assert_eq!(client.balance(&payee), 40);
+    assert!(client.try_transfer_from(&spender, &owner, &payee, &21).is_err());
+
+    // Expired allowances read as zero
+    env.ledger().with_mut(|li| { li.sequence_number = expiration + 1; });
+    assert_eq!(client.allowance(&owner, &spender), 0);
+}
+
+#[test]
+fn test_burn_moves_votes() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin) = setup(&env);
+    let user = Address::generate(&env);
+    let spender = Address::generate(&env);
+
+    client.mint(&admin, &user, &50);
+    client.burn(&user, &20);
+    client.approve(&user, &spender, &10, &(env.ledger().sequence() + 1));
+    client.burn_from(&spender, &user, &10);
+
+    assert_eq!(client.balance(&user), 20);
+    assert_eq!(client.get_votes(&user), 20);
+    assert_eq!(client.total_supply(), 20);
+}
+
+#[test]
+#[should_panic(expected = "Insufficient BZR")]
+fn test_overdraft_panics() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin) = setup(&env);
+    let user1 = Address::generate(&env);
+    let user2 = Address::generate(&env);
+
+    client.mint(&admin, &user1, &5);
+    client.transfer(&user1, &user2, &6);
+}
+
+// --- VOTE DELEGATION & SNAPSHOTS ---
+
+#[test]
+fn test_delegation_moves_voting_power() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin) = setup(&env);
+    let merchant = Address::generate(&env);
+    let rep = Address::generate(&env);
+
+    client.mint(&admin, &merchant, &100);
+    client.mint(&admin, &rep, &20);
+    let before = env.ledger().sequence();
+    next_ledger(&env);
+
+    client.delegate(&merchant, &rep);
+    assert_eq!(client.get_delegate(&merchant), rep);
+    assert_eq!(client.get_votes(&merchant), 0);
+    assert_eq!(client.get_votes(&rep), 120);
+
+    // Later balance changes follow the delegation
+    client.mint(&admin, &merchant, &5);
+    assert_eq!(client.get_votes(&rep), 125);
+
+    next_ledger(&env);
+    assert_eq!(client.get_past_votes(&merchant, &before), 100);
+    assert_eq!(client.get_past_votes(&rep, &before), 20);
+    assert_eq!(client.get_past_votes(&rep, &(before + 1)), 125);
+}
+
+#[test]
+fn test_transfer_moves_votes() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin) = setup(&env);
+    let holder = Address::generate(&env);
+    let sybil = Address::generate(&env);
+
+    client.mint(&admin, &holder, &40);
+    let before = env.ledger().sequence();
+    next_ledger(&env);
+
+    client.transfer(&holder, &sybil, &40);
+    assert_eq!(client.get_votes(&holder), 0);
+    assert_eq!(client.get_votes(&sybil), 40);
+    assert_eq!(client.get_past_votes(&holder, &before), 40);
+    assert_eq!(client.get_past_votes(&sybil, &before), 0);
+}
+
+#[test]
+fn test_checkpoints_are_stored_per_ledger() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin) = setup(&env);
+    let market = Address::generate(&env);
+    next_ledger(&env);
+    let start = env.ledger().sequence();
+
+    // A busy account: one checkpoint per active ledger, several changes folded into each
+    for i in 0..20 {
+        client.mint(&admin, &market, &10);
+        client.burn(&market, &5);
+        if i < 19 { next_ledger(&env); }
+    }
+    next_ledger(&env);
+    env.as_contract(&client.address, || {
+        assert_eq!(checkpoint_count(&env, &market), 20);
+        assert!(env.storage().persistent().has(&DataKey::Checkpoints(market.clone(), 19)));
+    });
+    for i in 0..20 {
+        assert_eq!(client.get_past_votes(&market, &(start + i)), 5 * (i as i128 + 1));
+    }
+    assert_eq!(client.get_past_votes(&market, &(start - 1)), 0);
+}
+
+#[test]
+#[should_panic(expected = "Ledger not yet finalized")]
+fn test_past_votes_rejects_current_ledger() {
+    let env = Env::default();
+    let (client, _) = setup(&env);
+    let user = Address::generate(&env);
+
+    client.get_past_votes(&user, &env.ledger().sequence());
+}
+
+// --- STORAGE UPKEEP ---
+
+#[test]
+fn test_bump_keeps_balances_and_vote_history_alive() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin) = setup(&env);
+    let holder = Address::generate(&env);
+    let minter = Address::generate(&env);
+    let day = 17_280;
+
+    client.set_minter(&minter, &true);
+    for _ in 0..3 {
+        client.mint(&admin, &holder, &10);
+        next_ledger(&env);
+    }
+    let records = [
+        DataKey::Balance(holder.clone()),
+        DataKey::CheckpointCount(holder.clone()),
+        DataKey::Checkpoints(holder.clone(), 0),
+        DataKey::Checkpoints(holder.clone(), 2),
+        DataKey::Minter(minter.clone()),
+    ];
+
+    env.ledger().with_mut(|li| { li.sequence_number += 80 * day; });
+    client.bump(&holder);
+    client.bump(&minter);
+    env.as_contract(&client.address, || {
+        for key in records.iter() {
+            assert_eq!(env.storage().persistent().get_ttl(key), 90 * day);
+        }
+    });
+}
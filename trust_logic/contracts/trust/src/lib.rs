@@ -0,0 +1,1711 @@
+// 🏛️ PROJECT BAZAAR | TRUST
+// Merchant reputation: Academy Trust Score, Poverty Observation, DAO Jury Appeals, the Badge Shop,
+// Academy Credentials, Referrals, the Nickname Registry, Encrypted Merchant Inboxes, Storage Upkeep,
+// and Upgrades. BZR moves through the Token contract; fees go to the Governance treasury.
+
+#![no_std]
+use bazaar_types::{BzrClient, Deployment, GovernanceClient, LegacyVault, MarketClient, Role, TierConfig, VaultClient, RECORD_TTL, RECORD_TTL_THRESHOLD};
+use soroban_sdk::xdr::ToXdr;
+use soroban_sdk::{contract, contractimpl, contracttype, symbol_short, token, Address, Bytes, BytesN, Env, Map, String, Symbol, SymbolStr, IntoVal, TryFromVal, Val, Vec};
+
+// ============================================================
+// 🔧 PROTOCOL CONSTANTS
+// ============================================================
+
+// Every persistent write goes through `store`, which tops the record up per `ttl_policy`;
+// `bump` and `bump_pair` let anyone do the same.
+const SCHEMA_VERSION: u32 = 3;          // 1: v3.1 layouts, 2: BZR balances on the merchant, 3: split contracts
+const MIGRATION_BATCH: u32 = 25;        // Users per `migrate` call
+
+const BOND_POINTS: u32 = 10;            // Trust points a bond carries while staked
+const BOND_LOCK: u64 = 2_592_000;       // 30 Days before a bond can be withdrawn
+const DECAY_BPS: u32 = 2_500;           // Share of trust lost per `decay`
+const VOUCH_REWARD: i128 = 5;           // BZR minted to the voucher for vouching for a bonded merchant
+const VOUCH_REWARD_CAP: u32 = 10;       // Rewarded vouches per voucher
+const DISPUTE_PENALTY: u32 = 10;        // Trust points lost when a dispute is upheld
+const APPEAL_DEPOSIT: i128 = 50;        // BZR the losing party locks to appeal
+const APPEAL_WINDOW: u64 = 259_200;     // 3 Days to file an appeal after a ruling
+const JURY_SIZE: u32 = 5;
+const JUROR_MIN_TRUST: u32 = 20;
+const JURY_COMMIT_PERIOD: u64 = 172_800; // 2 Days
+const JURY_REVEAL_PERIOD: u64 = 172_800; // 2 Days
+const JUROR_PENALTY: i128 = 5;          // Slashed from jurors who miss the reveal or vote with the minority
+
+const OBSERVATION_WINDOW: u64 = 604_800; // 7 Day Poverty Observation after bonding
+const REFERRAL_L1_REWARD: i128 = 10;
+const REFERRAL_L2_REWARD: i128 = 3;
+const REFERRAL_EPOCH_CAP: i128 = 100;   // Max referral BZR per referrer per epoch
+const REFERRAL_EPOCH: u64 = 2_592_000;  // 30 Days
+const NICKNAME_FEE: i128 = 10;          // "nick_fee": BZR to the treasury per registration, discourages squatting
+const NICKNAME_COOLDOWN: u64 = 604_800; // 7 Days before a dropped nickname can be claimed by others
+const NICKNAME_MIN_LEN: usize = 3;
+const NICKNAME_MAX_LEN: usize = 16;
+const INBOX_CAP: u32 = 100;             // Oldest messages are evicted past this
+const INBOX_PAGE_LIMIT: u32 = 50;
+const MAX_CIPHERTEXT: u32 = 1_024;      // Bytes per encrypted message, tag included
+const MESSAGE_MIN_TRUST: u32 = 10;      // Bonding alone clears this
+const MESSAGE_WINDOW: u32 = 17_280;     // Ledgers per rate-limit window (~1 Day at 5s)
+const RATE_TTL: u32 = 2 * MESSAGE_WINDOW; // A MessageRate record only matters for the current window
+const EXILE_PERIOD: u64 = 2_592_000;    // 30 Days of arbiter-only messaging after an upheld dispute
+
+// ============================================================
+// 📦 DATA STRUCTURES
+// ============================================================
+
+#[contracttype]
+#[derive(Clone)]
+pub struct Merchant {
+    pub trust_score: u32,
+    pub bond_staked: bool,
+    pub badges: Vec<Symbol>,
+    pub is_disputed: bool,
+    pub nickname: Symbol,
+}
+
+/// Trust score held by a merchant from `ledger` onward.
+#[contracttype]
+#[derive(Clone)]
+pub struct TrustCheckpoint {
+    pub ledger: u32,
+    pub score: u32,
+}
+
+/// Stored under `DataKey::InboxMessage(recipient, index)`, outside the merchant record.
+/// The body is X25519 box ciphertext; the key versions say which registry entries to decrypt with.
+#[contracttype]
+#[derive(Clone)]
+pub struct Message {
+    pub sender: Address,
+    pub ciphertext: Bytes,
+    pub nonce: BytesN<24>,
+    pub sender_key: u32,
+    pub recipient_key: u32,
+    pub timestamp: u64,
+    pub read: bool,
+}
+
+/// Messages a sender has used in the current `MESSAGE_WINDOW`.
+#[contracttype]
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct RateWindow {
+    pub window: u32,
+    pub sent: u32,
+}
+
+/// Published X25519 public key. Versions start at 1 and increase on every rotation.
+#[contracttype]
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct EncryptionKey {
+    pub key: BytesN<32>,
+    pub version: u32,
+    pub published_at: u64,
+}
+
+/// Live messages occupy indexes `head..next`.
+#[contracttype]
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Inbox {
+    pub head: u32,
+    pub next: u32,
+    pub unread: u32,
+}
+
+// --- Schema v1 and v2 layouts, only read when upgrading old records ---
+
+#[contracttype]
+#[derive(Clone)]
+pub struct MessageV1 {
+    pub sender: Address,
+    pub text: String,
+    pub timestamp: u64,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct MerchantV1 {
+    pub trust_score: u32,
+    pub bond_staked: bool,
+    pub bzr_balance: i128,
+    pub badges: Vec<Symbol>,
+    pub is_disputed: bool,
+    pub nickname: Symbol,
+    pub messages: Vec<MessageV1>,
+}
+
+/// v2 merchant: BZR was an internal balance before the Token contract existed.
+#[contracttype]
+#[derive(Clone)]
+pub struct MerchantV2 {
+    pub trust_score: u32,
+    pub bond_staked: bool,
+    pub bzr_balance: i128,
+    pub badges: Vec<Symbol>,
+    pub is_disputed: bool,
+    pub nickname: Symbol,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct LegacyVaultV1 {
+    pub heir: Option<Address>,
+    pub last_heartbeat: u64,
+    pub is_locked: bool,
+    pub is_frozen: bool,
+}
+
+/// 7-day monitoring window that starts when a merchant bonds.
+#[contracttype]
+#[derive(Clone)]
+pub struct PovertyObservation {
+    pub start_time: u64,
+    pub is_active: bool,
+}
+
+impl PovertyObservation {
+    pub fn start_observation(&mut self, now: u64) {
+        self.start_time = now;
+        self.is_active = true;
+    }
+
+    /// True once the window has fully elapsed; closes the observation at that point.
+    pub fn verify_window(&mut self, now: u64) -> bool {
+        if now < self.start_time + OBSERVATION_WINDOW { return false; }
+        self.is_active = false;
+        true
+    }
+}
+
+#[contracttype]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DisputeStatus {
+    Open,
+    Ruled,
+    Appealed,
+    Final,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct Dispute {
+    pub accuser: Address,
+    pub target: Address,
+    pub arbiter: Address,
+    pub status: DisputeStatus,
+    pub upheld: bool,
+    pub penalty: u32,
+    pub ruled_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct AppealConfig {
+    pub deposit: i128,
+    pub window: u64,
+    pub jury_size: u32,
+    pub min_trust: u32,
+    pub commit_period: u64,
+    pub reveal_period: u64,
+    pub juror_penalty: i128,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct Appeal {
+    pub id: u32,
+    pub appellant: Address,
+    pub deposit: i128,
+    pub jurors: Vec<Address>,
+    pub commits: Map<Address, BytesN<32>>,
+    pub reveals: Map<Address, bool>,
+    pub commit_deadline: u64,
+    pub reveal_deadline: u64,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct Juror {
+    pub stake: i128,
+    pub active_cases: u32,
+}
+
+/// Catalog entry. Academy badges are earned by course completion and only issued by an
+/// `AcademyIssuer`; the rest are sold for BZR. `max_supply` of 0 means unlimited.
+#[contracttype]
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct BadgeInfo {
+    pub name: String,
+    pub price: i128,
+    pub max_supply: u32,
+    pub minted: u32,
+    pub min_trust: u32,
+    pub soulbound: bool,
+    pub burn: bool,
+    pub academy: bool,
+}
+
+/// Course-completion credential. `content_hash` commits to the off-chain course record,
+/// e.g. the hash column of `Registry_Master_Ledger.csv`.
+#[contracttype]
+#[derive(Clone)]
+pub struct Credential {
+    pub course: Symbol,
+    pub issuer: Address,
+    pub issued_at: u64,
+    pub content_hash: BytesN<32>,
+    pub revoked: bool,
+}
+
+/// `level_rewards[0]` goes to the direct referrer, `[1]` to their referrer, and so on.
+#[contracttype]
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ReferralConfig {
+    pub level_rewards: Vec<i128>,
+    pub cap_per_epoch: i128,
+    pub epoch_length: u64,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub struct Referral {
+    pub referrer: Address,
+    pub settled: bool,
+}
+
+#[contracttype]
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ReferralStats {
+    pub referrer: Option<Address>,
+    pub referrals: u32,
+    pub pending: u32,
+    pub earned: i128,
+}
+
+/// Index entry for a lowercased nickname. A non-zero `release_at` means the owner dropped it
+/// and it becomes claimable by others from that time.
+#[contracttype]
+#[derive(Clone)]
+pub struct NicknameRecord {
+    pub owner: Address,
+    pub release_at: u64,
+}
+
+#[contracttype]
+pub enum DataKey {
+    Merchant(Address),
+    Admin,
+    Deployment,
+    Dispute(Address),
+    Appeal(Address),
+    AppealConfig,
+    AppealCount,
+    JurorPool,
+    Juror(Address),
+    RoleMembers(Role),
+    Badge(Symbol),
+    Credential(Address, Symbol),
+    Credentials(Address),
+    CredentialGate(Symbol),
+    Observation(Address),
+    Referral(Address),
+    ReferralStats(Address),
+    ReferralEarned(Address, u64),
+    ReferralConfig,
+    Nickname(Symbol),
+    ReservedNickname(Symbol),
+    Inbox(Address),
+    InboxMessage(Address, u32),
+    Blocked(Address, Address),
+    EncryptionKeys(Address),
+    SchemaVersion,
+    Vouch(Address, Address),
+    VouchRewards(Address),
+    Maintenance,
+    TrustCheckpoints(Address, u32),
+    TrustCheckpointCount(Address),
+    ContactsOnly(Address),
+    MessageRate(Address),
+    Unminted(Address),
+    // Pre-split records, only read and cleared by `migrate`
+    Vault(Address),
+    Witnesses(Address),
+    Treasury,
+}
+
+// ============================================================
+// ⚙️ THE CONTRACT
+// ============================================================
+#[contract]
+pub struct TrustContract;
+
+#[contractimpl]
+impl TrustContract {
+
+    /// Runs on deploy only, never on a wasm upgrade, so fresh deployments start on the current schema
+    /// and the admin seat is set in the same transaction that creates the contract.
+    pub fn __constructor(env: Env, admin: Address, deployment: Deployment) {
+        env.storage().instance().set(&DataKey::Admin, &admin);
+        env.storage().instance().set(&DataKey::Deployment, &deployment);
+        store(&env, &DataKey::SchemaVersion, &SCHEMA_VERSION);
+    }
+
+    // --- FEATURE 1: MERCHANT TRUST ---
+
+    pub fn stake(env: Env, user: Address, referrer: Option<Address>) {
+        user.require_auth();
+        require_live(&env);
+        let mut merchant = load_merchant(&env, &user);
+        if merchant.bond_staked { panic!("Already bonded"); }
+        merchant.bond_staked = true;
+        merchant.trust_score += BOND_POINTS;
+        save_merchant(&env, &user, &merchant);
+
+        let mut observation = PovertyObservation { start_time: 0, is_active: false };
+        observation.start_observation(env.ledger().timestamp());
+        store(&env, &DataKey::Observation(user.clone()), &observation);
+
+        if let Some(referrer) = referrer {
+            record_referral(&env, &user, referrer);
+        }
+    }
+
+    /// True once the merchant has cleared the 7-day Poverty Observation window.
+    pub fn verify_status(env: Env, user: Address) -> bool {
+        let key = DataKey::Observation(user);
+        let mut observation: PovertyObservation = match env.storage().persistent().get(&key) {
+            Some(o) => o,
+            None => return false,
+        };
+        let was_active = observation.is_active;
+        let cleared = observation.verify_window(env.ledger().timestamp());
+        if was_active != observation.is_active {
+            store(&env, &key, &observation);
+        }
+        cleared
+    }
+
+    /// Gives up the bond, and the trust it carried, once it has been staked for `BOND_LOCK`.
+    pub fn withdraw(env: Env, user: Address) {
+        user.require_auth();
+        let merchant = load_merchant(&env, &user);
+        if !merchant.bond_staked { panic!("Not bonded"); }
+        let observation: PovertyObservation = env.storage().persistent().get(&DataKey::Observation(user.clone())).expect("Not bonded");
+        if env.ledger().timestamp() < observation.start_time + BOND_LOCK { panic!("Bond is still locked"); }
+        unbond(&env, &user, merchant);
+    }
+
+    /// Admin only: strips a bond regardless of its lock.
+    pub fn force_unbond(env: Env, user: Address) {
+        require_admin(&env);
+        let merchant = load_merchant(&env, &user);
+        if !merchant.bond_staked { panic!("Not bonded"); }
+        unbond(&env, &user, merchant);
+    }
+
+    /// Admin only: one trust point for off-chain good standing, capped like vouches.
+    pub fn add_trust(env: Env, user: Address) {
+        require_admin(&env);
+        let mut merchant = load_merchant(&env, &user);
+        if merchant.trust_score < 100 { merchant.trust_score += 1; }
+        save_merchant(&env, &user, &merchant);
+    }
+
+    /// Admin only: an inactive merchant loses `DECAY_BPS` of their trust, rounded against them.
+    pub fn decay(env: Env, user: Address) {
+        require_admin(&env);
+        let mut merchant = load_merchant(&env, &user);
+        merchant.trust_score = merchant.trust_score * (10_000 - DECAY_BPS) / 10_000;
+        save_merchant(&env, &user, &merchant);
+    }
+
+    /// One vouch per pair, and only from bonded merchants vouching for someone else.
+    /// Vouching for a bonded merchant pays `VOUCH_REWARD`, for up to `VOUCH_REWARD_CAP` vouches.
+    /// Rewards are cut, never reverted, when the BZR reserve is at its floor.
+    pub fn vouch(env: Env, voucher: Address, target: Address) {
+        voucher.require_auth();
+        require_live(&env);
+        if voucher == target { panic!("Cannot vouch for yourself"); }
+        if !load_merchant(&env, &voucher).bond_staked { panic!("Voucher not bonded"); }
+        let vouch_key = DataKey::Vouch(voucher.clone(), target.clone());
+        if env.storage().persistent().has(&vouch_key) { panic!("Already vouched"); }
+        // SAFE INITIALIZATION: No more "Target not found" traps
+        let mut target_data = stored_merchant(&env, &target).unwrap_or(Merchant {
+            trust_score: 0, bond_staked: false, badges: Vec::new(&env), 
+            is_disputed: false, nickname: Symbol::new(&env, "NewUser")
+        });
+
+        if target_data.trust_score < 100 { target_data.trust_score += 1; }
+        save_merchant(&env, &target, &target_data);
+        store(&env, &vouch_key, &true);
+
+        let rewards_key = DataKey::VouchRewards(voucher.clone());
+        let rewarded: u32 = env.storage().persistent().get(&rewards_key).unwrap_or(0);
+        if target_data.bond_staked && rewarded < VOUCH_REWARD_CAP && mint(&env, &voucher, VOUCH_REWARD) > 0 {
+            store(&env, &rewards_key, &(rewarded + 1));
+        }
+    }
+
+    pub fn has_vouched(env: Env, voucher: Address, target: Address) -> bool {
+        env.storage().persistent().has(&DataKey::Vouch(voucher, target))
+    }
+
+    pub fn get_trust(env: Env, user: Address) -> u32 {
+        load_merchant(&env, &user).trust_score
+    }
+
+    /// Trust score at the end of a ledger that has already closed, so DAO votes can weigh
+    /// trust as of a proposal's creation.
+    pub fn get_past_trust(env: Env, user: Address, ledger: u32) -> u32 {
+        if ledger >= env.ledger().sequence() { panic!("Ledger not yet finalized"); }
+        trust_at(&env, &user, ledger)
+    }
+
+    pub fn is_bonded(env: Env, user: Address) -> bool {
+        load_merchant(&env, &user).bond_staked
+    }
+
+    // --- FEATURE 2: ADMIN & ROLES ---
+
+    pub fn get_admin(env: Env) -> Address {
+        env.storage().instance().get(&DataKey::Admin).unwrap()
+    }
+
+    pub fn get_deployment(env: Env) -> Deployment {
+        deployment(&env)
+    }
+
+    pub fn has_role(env: Env, role: Role, who: Address) -> bool {
+        has_role(&env, role, &who)
+    }
+
+    pub fn grant_role(env: Env, role: Role, who: Address) {
+        require_admin(&env);
+        grant_role(&env, role, who);
+    }
+
+    pub fn revoke_role(env: Env, role: Role, who: Address) {
+        require_admin(&env);
+        revoke_role(&env, role, who);
+    }
+
+    /// Pauses new bonds and vouches, e.g. while a migration is running.
+    pub fn set_maintenance(env: Env, enabled: bool) {
+        require_admin(&env);
+        env.storage().instance().set(&DataKey::Maintenance, &enabled);
+    }
+
+    pub fn is_maintenance(env: Env) -> bool {
+        env.storage().instance().get(&DataKey::Maintenance).unwrap_or(false)
+    }
+
+    // --- FEATURE 3: DISPUTES & DAO JURY APPEALS ---
+
+    pub fn raise_dispute(env: Env, accuser: Address, target: Address) {
+        accuser.require_auth();
+        if accuser == target { panic!("Cannot dispute yourself"); }
+        let mut merchant = stored_merchant(&env, &target).expect("Target not found");
+        if let Some(existing) = env.storage().persistent().get::<_, Dispute>(&DataKey::Dispute(target.clone())) {
+            if !is_settled(&env, &existing) { panic!("Dispute active"); }
+        }
+
+        let dispute = Dispute {
+            arbiter: pick_arbiter(&env, &accuser, &target),
+            accuser,
+            target: target.clone(),
+            status: DisputeStatus::Open,
+            upheld: false,
+            penalty: 0,
+            ruled_at: 0,
+        };
+        store(&env, &DataKey::Dispute(target.clone()), &dispute);
+        merchant.is_disputed = true;
+        save_merchant(&env, &target, &merchant);
+    }
+
+    pub fn is_disputed(env: Env, target: Address) -> bool {
+        load_merchant(&env, &target).is_disputed
+    }
+
+    pub fn get_dispute(env: Env, target: Address) -> Option<Dispute> {
+        env.storage().persistent().get(&DataKey::Dispute(target))
+    }
+
+    /// Arbiter's first-instance ruling. `upheld` means the accusation stands against the target.
+    pub fn resolve_dispute(env: Env, target: Address, upheld: bool) {
+        let key = DataKey::Dispute(target.clone());
+        let mut dispute: Dispute = env.storage().persistent().get(&key).expect("No dispute");
+        dispute.arbiter.require_auth();
+        if dispute.status != DisputeStatus::Open { panic!("Dispute already ruled"); }
+
+        dispute.status = DisputeStatus::Ruled;
+        dispute.ruled_at = env.ledger().timestamp();
+        apply_ruling(&env, &mut dispute, upheld);
+        store(&env, &key, &dispute);
+    }
+
+    pub fn set_appeal_config(env: Env, config: AppealConfig) {
+        require_admin(&env);
+        if config.jury_size == 0 || config.jury_size.is_multiple_of(2) { panic!("Jury size must be odd"); }
+        if config.deposit <= 0 || config.juror_penalty < 0 { panic!("Invalid amount"); }
+        store(&env, &DataKey::AppealConfig, &config);
+    }
+
+    pub fn get_appeal_config(env: Env) -> AppealConfig {
+        appeal_config(&env)
+    }
+
+    /// Locks BZR as juror stake. Jury seats are drawn weighted by this stake.
+    pub fn join_jury_pool(env: Env, juror: Address, amount: i128) {
+        juror.require_auth();
+        if amount <= 0 { panic!("Invalid amount"); }
+        check_gate(&env, symbol_short!("jury"), &juror);
+        bzr(&env).transfer(&juror, env.current_contract_address(), &amount);
+
+        let key = DataKey::Juror(juror.clone());
+        let mut record: Juror = env.storage().persistent().get(&key).unwrap_or(Juror { stake: 0, active_cases: 0 });
+        if record.stake == 0 {
+            let mut pool: Vec<Address> = env.storage().persistent().get(&DataKey::JurorPool).unwrap_or(Vec::new(&env));
+            pool.push_back(juror.clone());
+            store(&env, &DataKey::JurorPool, &pool);
+        }
+        record.stake += amount;
+        // Stakes are draw weights, so they must fit the jury draw's u64 arithmetic
+        if u64::try_from(record.stake).is_err() { panic!("Juror stake out of range"); }
+        store(&env, &key, &record);
+    }
+
+    pub fn leave_jury_pool(env: Env, juror: Address) {
+        juror.require_auth();
+        let key = DataKey::Juror(juror.clone());
+        let record: Juror = env.storage().persistent().get(&key).expect("Not a juror");
+        if record.active_cases > 0 { panic!("Juror is seated on a case"); }
+
+        let mut pool: Vec<Address> = env.storage().persistent().get(&DataKey::JurorPool).unwrap_or(Vec::new(&env));
+        if let Some(i) = pool.first_index_of(juror.clone()) { pool.remove(i); }
+        store(&env, &DataKey::JurorPool, &pool);
+        env.storage().persistent().remove(&key);
+        bzr(&env).transfer(&env.current_contract_address(), &juror, &record.stake);
+    }
+
+    pub fn get_juror(env: Env, juror: Address) -> Option<Juror> {
+        env.storage().persistent().get(&DataKey::Juror(juror))
+    }
+
+    /// The losing party locks the appeal deposit and a stake-weighted jury is drawn.
+    pub fn appeal_dispute(env: Env, appellant: Address, target: Address) {
+        appellant.require_auth();
+        let key = DataKey::Dispute(target.clone());
+        let mut dispute: Dispute = env.storage().persistent().get(&key).expect("No dispute");
+        if dispute.status != DisputeStatus::Ruled { panic!("Dispute not appealable"); }
+
+        let loser = if dispute.upheld { dispute.target.clone() } else { dispute.accuser.clone() };
+        if appellant != loser { panic!("Only the losing party can appeal"); }
+
+        let config = appeal_config(&env);
+        let now = env.ledger().timestamp();
+        if now > dispute.ruled_at + config.window { panic!("Appeal window closed"); }
+
+        bzr(&env).transfer(&appellant, env.current_contract_address(), &config.deposit);
+        let jurors = draw_jury(&env, &dispute, &config);
+
+        let id: u32 = env.storage().persistent().get(&DataKey::AppealCount).unwrap_or(0) + 1;
+        store(&env, &DataKey::AppealCount, &id);
+        let appeal = Appeal {
+            id,
+            appellant,
+            deposit: config.deposit,
+            jurors,
+            commits: Map::new(&env),
+            reveals: Map::new(&env),
+            commit_deadline: now + config.commit_period,
+            reveal_deadline: now + config.commit_period + config.reveal_period,
+        };
+        store(&env, &DataKey::Appeal(target.clone()), &appeal);
+
+        dispute.status = DisputeStatus::Appealed;
+        store(&env, &key, &dispute);
+        set_disputed(&env, &target, true);
+    }
+
+    pub fn get_appeal(env: Env, target: Address) -> Option<Appeal> {
+        env.storage().persistent().get(&DataKey::Appeal(target))
+    }
+
+    /// `commitment` is sha256(vote_byte || salt || juror || appeal id), where vote_byte is 1 to uphold
+    /// the accusation, the juror is XDR-encoded and the id is big-endian.
+    pub fn commit_vote(env: Env, juror: Address, target: Address, commitment: BytesN<32>) {
+        juror.require_auth();
+        let key = DataKey::Appeal(target);
+        let mut appeal: Appeal = env.storage().persistent().get(&key).expect("No appeal");
+        if !appeal.jurors.contains(juror.clone()) { panic!("Not a juror"); }
+        if env.ledger().timestamp() > appeal.commit_deadline { panic!("Commit phase over"); }
+
+        appeal.commits.set(juror, commitment);
+        store(&env, &key, &appeal);
+    }
+
+    pub fn reveal_vote(env: Env, juror: Address, target: Address, upheld: bool, salt: BytesN<32>) {
+        juror.require_auth();
+        let key = DataKey::Appeal(target);
+        let mut appeal: Appeal = env.storage().persistent().get(&key).expect("No appeal");
+        let now = env.ledger().timestamp();
+        if now <= appeal.commit_deadline { panic!("Reveal phase not started"); }
+        if now > appeal.reveal_deadline { panic!("Reveal phase over"); }
+        if appeal.reveals.contains_key(juror.clone()) { panic!("Already revealed"); }
+
+        let commitment = appeal.commits.get(juror.clone()).expect("No commitment");
+        if vote_commitment(&env, &juror, appeal.id, upheld, &salt) != commitment { panic!("Commitment mismatch"); }
+
+        appeal.reveals.set(juror, upheld);
+        store(&env, &key, &appeal);
+    }
+
+    /// Permissionless once the reveal phase ends. The jury's verdict is final.
+    pub fn finalize_appeal(env: Env, target: Address) {
+        let appeal_key = DataKey::Appeal(target.clone());
+        let appeal: Appeal = env.storage().persistent().get(&appeal_key).expect("No appeal");
+        if env.ledger().timestamp() <= appeal.reveal_deadline { panic!("Reveal phase not over"); }
+
+        let dispute_key = DataKey::Dispute(target.clone());
+        let mut dispute: Dispute = env.storage().persistent().get(&dispute_key).expect("No dispute");
+        let config = appeal_config(&env);
+
+        let mut uphold_votes: u32 = 0;
+        let mut reject_votes: u32 = 0;
+        for (_, vote) in appeal.reveals.iter() {
+            if vote { uphold_votes += 1; } else { reject_votes += 1; }
+        }
+        // A tied or silent jury leaves the first ruling in place.
+        let verdict = if uphold_votes == reject_votes { dispute.upheld } else { uphold_votes > reject_votes };
+        let has_majority = uphold_votes != reject_votes;
+
+        let mut pot: i128 = 0;
+        if verdict == dispute.upheld {
+            pot += appeal.deposit;
+        } else {
+            bzr(&env).transfer(&env.current_contract_address(), &appeal.appellant, &appeal.deposit);
+        }
+
+        let mut majority: Vec<Address> = Vec::new(&env);
+        for juror in appeal.jurors.iter() {
+            let key = DataKey::Juror(juror.clone());
+            let mut record: Juror = env.storage().persistent().get(&key).expect("Not a juror");
+            record.active_cases -= 1;
+            match appeal.reveals.get(juror.clone()) {
+                Some(vote) if has_majority && vote == verdict => majority.push_back(juror.clone()),
+                // A tie has no minority; only jurors who never revealed are slashed
+                Some(_) if !has_majority => {}
+                _ => {
+                    let slash = if record.stake < config.juror_penalty { record.stake } else { config.juror_penalty };
+                    record.stake -= slash;
+                    pot += slash;
+                }
+            }
+            store(&env, &key, &record);
+        }
+
+        if !majority.is_empty() {
+            let share = pot / majority.len() as i128;
+            for juror in majority.iter() {
+                bzr(&env).transfer(&env.current_contract_address(), &juror, &share);
+            }
+            pot -= share * majority.len() as i128;
+        }
+        pay_treasury(&env, &env.current_contract_address(), pot);
+
+        apply_ruling(&env, &mut dispute, verdict);
+        dispute.status = DisputeStatus::Final;
+        store(&env, &dispute_key, &dispute);
+        env.storage().persistent().remove(&appeal_key);
+    }
+
+    // --- FEATURE 4: BADGE SHOP ---
+
+    /// Adds or updates a catalog entry. The minted count is owned by the contract and kept.
+    pub fn set_badge(env: Env, badge: Symbol, info: BadgeInfo) {
+        require_admin(&env);
+        if info.price < 0 { panic!("Invalid amount"); }
+        let key = DataKey::Badge(badge);
+        let minted = env.storage().persistent().get::<_, BadgeInfo>(&key).map(|b| b.minted).unwrap_or(0);
+        store(&env, &key, &BadgeInfo { minted, ..info });
+    }
+
+    pub fn get_badge(env: Env, badge: Symbol) -> BadgeInfo {
+        env.storage().persistent().get(&DataKey::Badge(badge)).expect("Badge not in catalog")
+    }
+
+    pub fn buy_badge(env: Env, user: Address, badge: Symbol) {
+        user.require_auth();
+        let info = Self::get_badge(env.clone(), badge.clone());
+        if info.academy { panic!("Academy badges cannot be bought"); }
+        if load_merchant(&env, &user).trust_score < info.min_trust { panic!("Trust score too low"); }
+
+        if info.burn {
+            bzr(&env).burn(&user, &info.price);
+        } else {
+            pay_treasury(&env, &user, info.price);
+        }
+        mint_badge(&env, &user, badge, info);
+    }
+
+    /// Academy issuers award course-completion badges such as "Module 01: Uptime Shield".
+    pub fn issue_badge(env: Env, issuer: Address, user: Address, badge: Symbol) {
+        issuer.require_auth();
+        if !has_role(&env, Role::AcademyIssuer, &issuer) { panic!("Not an academy issuer"); }
+        let info = Self::get_badge(env.clone(), badge.clone());
+        if !info.academy { panic!("Not an academy badge"); }
+        mint_badge(&env, &user, badge, info);
+    }
+
+    pub fn has_badge(env: Env, user: Address, badge: Symbol) -> bool {
+        load_merchant(&env, &user).badges.contains(badge)
+    }
+
+    pub fn transfer_badge(env: Env, from: Address, to: Address, badge: Symbol) {
+        from.require_auth();
+        let info = Self::get_badge(env.clone(), badge.clone());
+        if info.soulbound { panic!("Badge is soulbound"); }
+
+        let mut sender = load_merchant(&env, &from);
+        let i = sender.badges.first_index_of(badge.clone()).expect("Badge not owned");
+        let mut receiver = load_merchant(&env, &to);
+        if receiver.badges.contains(badge.clone()) { panic!("Badge already owned"); }
+        sender.badges.remove(i);
+        receiver.badges.push_back(badge);
+        save_merchant(&env, &from, &sender);
+        save_merchant(&env, &to, &receiver);
+    }
+
+    // --- FEATURE 5: ACADEMY CREDENTIALS ---
+
+    pub fn issue_credential(env: Env, instructor: Address, holder: Address, course: Symbol, content_hash: BytesN<32>) {
+        instructor.require_auth();
+        if !has_role(&env, Role::Instructor, &instructor) { panic!("Not an instructor"); }
+        let key = DataKey::Credential(holder.clone(), course.clone());
+        if let Some(existing) = env.storage().persistent().get::<_, Credential>(&key) {
+            if !existing.revoked { panic!("Credential already issued"); }
+        } else {
+            let list_key = DataKey::Credentials(holder.clone());
+            let mut courses: Vec<Symbol> = env.storage().persistent().get(&list_key).unwrap_or(Vec::new(&env));
+            courses.push_back(course.clone());
+            store(&env, &list_key, &courses);
+        }
+
+        let credential = Credential {
+            course,
+            issuer: instructor,
+            issued_at: env.ledger().timestamp(),
+            content_hash,
+            revoked: false,
+        };
+        store(&env, &key, &credential);
+    }
+
+    /// Any instructor may revoke. The record is kept, flagged as revoked.
+    pub fn revoke_credential(env: Env, instructor: Address, holder: Address, course: Symbol) {
+        instructor.require_auth();
+        if !has_role(&env, Role::Instructor, &instructor) { panic!("Not an instructor"); }
+        let key = DataKey::Credential(holder, course);
+        let mut credential: Credential = env.storage().persistent().get(&key).expect("Credential not found");
+        credential.revoked = true;
+        store(&env, &key, &credential);
+    }
+
+    pub fn has_credential(env: Env, holder: Address, course: Symbol) -> bool {
+        has_credential(&env, &holder, course)
+    }
+
+    pub fn get_credential(env: Env, holder: Address, course: Symbol) -> Option<Credential> {
+        env.storage().persistent().get(&DataKey::Credential(holder, course))
+    }
+
+    /// Every course ever credentialed to `holder`, including revoked ones.
+    pub fn get_credentials(env: Env, holder: Address) -> Vec<Symbol> {
+        env.storage().persistent().get(&DataKey::Credentials(holder)).unwrap_or(Vec::new(&env))
+    }
+
+    /// Requires `course` to use `feature` (e.g. "jury", "proposal"). `None` lifts the gate.
+    pub fn set_credential_gate(env: Env, feature: Symbol, course: Option<Symbol>) {
+        require_admin(&env);
+        let key = DataKey::CredentialGate(feature);
+        match course {
+            Some(course) => store(&env, &key, &course),
+            None => env.storage().persistent().remove(&key),
+        }
+    }
+
+    pub fn get_credential_gate(env: Env, feature: Symbol) -> Option<Symbol> {
+        env.storage().persistent().get(&DataKey::CredentialGate(feature))
+    }
+
+    /// True when `user` may use `feature`. Other contracts check their gates through this.
+    pub fn meets_gate(env: Env, feature: Symbol, user: Address) -> bool {
+        meets_gate(&env, feature, &user)
+    }
+
+    // --- FEATURE 6: REFERRALS ---
+
+    pub fn set_referral_config(env: Env, config: ReferralConfig) {
+        require_admin(&env);
+        if config.epoch_length == 0 || config.cap_per_epoch < 0 { panic!("Invalid referral config"); }
+        for reward in config.level_rewards.iter() {
+            if reward < 0 { panic!("Invalid referral config"); }
+        }
+        store(&env, &DataKey::ReferralConfig, &config);
+    }
+
+    pub fn get_referral_config(env: Env) -> ReferralConfig {
+        referral_config(&env)
+    }
+
+    /// Permissionless. Pays the referral chain once the referred merchant has survived the
+    /// Poverty Observation window still bonded and undisputed. Each payout is clipped to
+    /// what the recipient may still earn this epoch.
+    pub fn settle_referral(env: Env, user: Address) {
+        let key = DataKey::Referral(user.clone());
+        let mut referral: Referral = env.storage().persistent().get(&key).expect("No referral");
+        if referral.settled { panic!("Referral already settled"); }
+        if !Self::verify_status(env.clone(), user.clone()) { panic!("Observation window active"); }
+        let merchant = load_merchant(&env, &user);
+        if !merchant.bond_staked || merchant.is_disputed { panic!("Referred user did not survive observation"); }
+
+        referral.settled = true;
+        store(&env, &key, &referral);
+
+        let config = referral_config(&env);
+        let epoch = env.ledger().timestamp() / config.epoch_length;
+        let mut recipient = referral.referrer.clone();
+        for (level, reward) in config.level_rewards.iter().enumerate() {
+            let earned_key = DataKey::ReferralEarned(recipient.clone(), epoch);
+            let earned: i128 = env.storage().persistent().get(&earned_key).unwrap_or(0);
+            let payout = mint(&env, &recipient, reward.min(config.cap_per_epoch - earned));
+
+            let mut stats = referral_stats(&env, &recipient);
+            if level == 0 { stats.pending -= 1; }
+            if payout > 0 {
+                store(&env, &earned_key, &(earned + payout));
+                stats.earned += payout;
+            }
+            store(&env, &DataKey::ReferralStats(recipient.clone()), &stats);
+
+            match env.storage().persistent().get::<_, Referral>(&DataKey::Referral(recipient.clone())) {
+                Some(next) => recipient = next.referrer,
+                None => break,
+            }
+        }
+    }
+
+    pub fn get_referral_stats(env: Env, user: Address) -> ReferralStats {
+        referral_stats(&env, &user)
+    }
+
+    // --- FEATURE 7: NICKNAME REGISTRY ---
+
+    /// Names are 3-16 chars, start with a letter and are unique case-insensitively.
+    pub fn set_nickname(env: Env, user: Address, nickname: Symbol) {
+        user.require_auth();
+        let name = nickname_key(&env, &nickname);
+        if load_merchant(&env, &user).nickname == nickname { return; } // Already held, nothing to charge
+        if env.storage().persistent().has(&DataKey::ReservedNickname(name.clone())) { panic!("Nickname reserved"); }
+        let key = DataKey::Nickname(name);
+
+        let now = env.ledger().timestamp();
+        if let Some(record) = env.storage().persistent().get::<_, NicknameRecord>(&key) {
+            if record.owner != user {
+                if record.release_at == 0 { panic!("Nickname taken"); }
+                if now < record.release_at { panic!("Nickname cooling down"); }
+            }
+        }
+
+        let fee = param(&env, symbol_short!("nick_fee"), NICKNAME_FEE);
+        pay_treasury(&env, &user, fee);
+
+        release_nickname(&env, &user);
+        store(&env, &key, &NicknameRecord { owner: user.clone(), release_at: 0 });
+        let mut merchant = load_merchant(&env, &user);
+        merchant.nickname = nickname;
+        save_merchant(&env, &user, &merchant);
+    }
+
+    /// Drops the current nickname. Others can claim it once the cooldown ends.
+    pub fn clear_nickname(env: Env, user: Address) {
+        user.require_auth();
+        release_nickname(&env, &user);
+        let mut merchant = load_merchant(&env, &user);
+        merchant.nickname = Symbol::new(&env, "User");
+        save_merchant(&env, &user, &merchant);
+    }
+
+    pub fn get_nickname(env: Env, user: Address) -> Symbol {
+        load_merchant(&env, &user).nickname
+    }
+
+    pub fn get_address_by_nickname(env: Env, nickname: Symbol) -> Option<Address> {
+        let record: NicknameRecord = env.storage().persistent().get(&DataKey::Nickname(nickname_key(&env, &nickname)))?;
+        if record.release_at == 0 { Some(record.owner) } else { None }
+    }
+
+    pub fn reserve_nickname(env: Env, nickname: Symbol) {
+        require_admin(&env);
+        store(&env, &DataKey::ReservedNickname(nickname_key(&env, &nickname)), &true);
+    }
+
+    pub fn unreserve_nickname(env: Env, nickname: Symbol) {
+        require_admin(&env);
+        env.storage().persistent().remove(&DataKey::ReservedNickname(nickname_key(&env, &nickname)));
+    }
+
+    // --- FEATURE 8: MERCHANT INBOX ---
+
+    /// Publishes a new X25519 public key. Earlier keys stay in the history so old messages remain readable.
+    pub fn publish_key(env: Env, user: Address, key: BytesN<32>) -> u32 {
+        user.require_auth();
+        let history_key = DataKey::EncryptionKeys(user);
+        let mut history: Vec<EncryptionKey> = env.storage().persistent().get(&history_key).unwrap_or(Vec::new(&env));
+        let version = history.len() + 1;
+        history.push_back(EncryptionKey { key, version, published_at: env.ledger().timestamp() });
+        store(&env, &history_key, &history);
+        version
+    }
+
+    pub fn get_encryption_key(env: Env, user: Address) -> Option<EncryptionKey> {
+        let history: Vec<EncryptionKey> = env.storage().persistent().get(&DataKey::EncryptionKeys(user))?;
+        history.last()
+    }
+
+    pub fn get_key_history(env: Env, user: Address) -> Vec<EncryptionKey> {
+        env.storage().persistent().get(&DataKey::EncryptionKeys(user)).unwrap_or(Vec::new(&env))
+    }
+
+    /// Both parties need a published key; the message is stamped with the current version of each.
+    /// Senders must be bonded and trusted, and stay within their tier's quota per ledger window.
+    /// Disputed or exiled merchants may only write to their arbiter.
+    pub fn send_message(env: Env, sender: Address, recipient: Address, ciphertext: Bytes, nonce: BytesN<24>) {
+        sender.require_auth();
+        if env.storage().persistent().has(&DataKey::Blocked(recipient.clone(), sender.clone())) { panic!("Sender blocked"); }
+        if ciphertext.is_empty() || ciphertext.len() > MAX_CIPHERTEXT { panic!("Invalid ciphertext size"); }
+        check_sender(&env, &sender, &recipient);
+        consume_quota(&env, &sender);
+        let sender_key = Self::get_encryption_key(env.clone(), sender.clone()).expect("Sender has no encryption key").version;
+        let recipient_key = Self::get_encryption_key(env.clone(), recipient.clone()).expect("Recipient has no encryption key").version;
+
+        let key = DataKey::Inbox(recipient.clone());
+        let mut inbox = inbox_of(&env, &recipient);
+        if inbox.next - inbox.head >= INBOX_CAP {
+            let oldest = DataKey::InboxMessage(recipient.clone(), inbox.head);
+            if let Some(evicted) = env.storage().persistent().get::<_, Message>(&oldest) {
+                if !evicted.read { inbox.unread -= 1; }
+            }
+            env.storage().persistent().remove(&oldest);
+            inbox.head += 1;
+        }
+
+        let message = Message {
+            sender,
+            ciphertext,
+            nonce,
+            sender_key,
+            recipient_key,
+            timestamp: env.ledger().timestamp(),
+            read: false,
+        };
+        store(&env, &DataKey::InboxMessage(recipient, inbox.next), &message);
+        inbox.next += 1;
+        inbox.unread += 1;
+        store(&env, &key, &inbox);
+    }
+
+    /// Up to `limit` messages starting at index `start`; indexes below the oldest retained message are skipped.
+    pub fn get_messages(env: Env, user: Address, start: u32, limit: u32) -> Vec<Message> {
+        let inbox = inbox_of(&env, &user);
+        let mut page = Vec::new(&env);
+        let mut i = start.max(inbox.head);
+        while i < inbox.next && page.len() < limit.min(INBOX_PAGE_LIMIT) {
+            if let Some(message) = env.storage().persistent().get(&DataKey::InboxMessage(user.clone(), i)) {
+                page.push_back(message);
+            }
+            i += 1;
+        }
+        page
+    }
+
+    pub fn get_inbox(env: Env, user: Address) -> Inbox {
+        inbox_of(&env, &user)
+    }
+
+    pub fn mark_read(env: Env, user: Address, index: u32) {
+        user.require_auth();
+        let key = DataKey::InboxMessage(user.clone(), index);
+        let mut message: Message = env.storage().persistent().get(&key).expect("Message not found");
+        if message.read { return; }
+        message.read = true;
+        store(&env, &key, &message);
+
+        let mut inbox = inbox_of(&env, &user);
+        inbox.unread -= 1;
+        store(&env, &DataKey::Inbox(user), &inbox);
+    }
+
+    pub fn block_sender(env: Env, user: Address, sender: Address) {
+        user.require_auth();
+        store(&env, &DataKey::Blocked(user, sender), &true);
+    }
+
+    pub fn unblock_sender(env: Env, user: Address, sender: Address) {
+        user.require_auth();
+        env.storage().persistent().remove(&DataKey::Blocked(user, sender));
+    }
+
+    pub fn is_blocked(env: Env, user: Address, sender: Address) -> bool {
+        env.storage().persistent().has(&DataKey::Blocked(user, sender))
+    }
+
+    /// When enabled, only merchants this user has vouched for can message them.
+    pub fn set_contacts_only(env: Env, user: Address, enabled: bool) {
+        user.require_auth();
+        let key = DataKey::ContactsOnly(user);
+        if enabled {
+            store(&env, &key, &true);
+        } else {
+            env.storage().persistent().remove(&key);
+        }
+    }
+
+    pub fn is_contacts_only(env: Env, user: Address) -> bool {
+        env.storage().persistent().has(&DataKey::ContactsOnly(user))
+    }
+
+    /// Messages left for `user` in the current rate-limit window.
+    pub fn get_message_allowance(env: Env, user: Address) -> u32 {
+        perks_of(&env, &user).message_quota.saturating_sub(rate_window(&env, &user).sent)
+    }
+
+    // --- FEATURE 9: STORAGE UPKEEP ---
+
+    /// Permissionless: tops up the TTL of everything `user` owns, the shared records they rely on,
+    /// and the contract itself.
+    pub fn bump(env: Env, user: Address) {
+        env.storage().instance().extend_ttl(RECORD_TTL_THRESHOLD, RECORD_TTL);
+        let merchant = stored_merchant(&env, &user);
+        let epoch = env.ledger().timestamp() / referral_config(&env).epoch_length;
+        for key in [
+            DataKey::Merchant(user.clone()),
+            DataKey::Observation(user.clone()),
+            DataKey::Dispute(user.clone()),
+            DataKey::Appeal(user.clone()),
+            DataKey::Juror(user.clone()),
+            DataKey::Credentials(user.clone()),
+            DataKey::Referral(user.clone()),
+            DataKey::ReferralStats(user.clone()),
+            DataKey::ReferralEarned(user.clone(), epoch),
+            DataKey::Inbox(user.clone()),
+            DataKey::EncryptionKeys(user.clone()),
+            DataKey::ContactsOnly(user.clone()),
+            DataKey::MessageRate(user.clone()),
+            DataKey::VouchRewards(user.clone()),
+            DataKey::Unminted(user.clone()),
+            DataKey::TrustCheckpointCount(user.clone()),
+            DataKey::JurorPool,
+            DataKey::AppealConfig,
+            DataKey::AppealCount,
+            DataKey::ReferralConfig,
+            DataKey::SchemaVersion,
+        ] {
+            extend_record(&env, &key);
+        }
+        for role in [Role::Admin, Role::Arbiter, Role::AcademyIssuer, Role::Instructor] {
+            extend_record(&env, &DataKey::RoleMembers(role));
+        }
+        // Older checkpoints back `get_past_trust` for votes still running, so the whole history is kept
+        for index in 0..trust_checkpoint_count(&env, &user) {
+            extend_record(&env, &DataKey::TrustCheckpoints(user.clone(), index));
+        }
+        let courses: Vec<Symbol> = env.storage().persistent().get(&DataKey::Credentials(user.clone())).unwrap_or(Vec::new(&env));
+        for course in courses.iter() {
+            extend_record(&env, &DataKey::Credential(user.clone(), course));
+        }
+        let inbox = inbox_of(&env, &user);
+        for i in inbox.head..inbox.next {
+            extend_record(&env, &DataKey::InboxMessage(user.clone(), i));
+        }
+        if let Some(merchant) = merchant {
+            extend_record(&env, &DataKey::Nickname(lowercase(&env, &merchant.nickname)));
+            for badge in merchant.badges.iter() {
+                extend_record(&env, &DataKey::Badge(badge));
+            }
+        }
+    }
+
+    /// Permissionless: tops up the records kept per pair of merchants, i.e. vouches and blocks
+    /// in either direction, which `bump` cannot enumerate.
+    pub fn bump_pair(env: Env, user: Address, other: Address) {
+        for key in [
+            DataKey::Vouch(user.clone(), other.clone()),
+            DataKey::Vouch(other.clone(), user.clone()),
+            DataKey::Blocked(user.clone(), other.clone()),
+            DataKey::Blocked(other, user),
+        ] {
+            extend_record(&env, &key);
+        }
+    }
+
+    // --- FEATURE 10: UPGRADES & MIGRATION ---
+
+    /// Admin path; the DAO can do the same through an `Upgrade` proposal on the Governance contract.
+    pub fn upgrade(env: Env, new_wasm_hash: BytesN<32>) {
+        require_admin(&env);
+        env.deployer().update_current_contract_wasm(new_wasm_hash);
+    }
+
+    pub fn get_schema_version(env: Env) -> u32 {
+        schema_version(&env)
+    }
+
+    /// Rewrites pre-split `Merchant` records for `users` into the current layout, minting their
+    /// old internal BZR balance (and any juror stake, to this contract) as real tokens, and moves
+    /// their vault and witnesses to the Vault contract. This contract must be a BZR minter.
+    /// Storage can't be enumerated on-chain, so an indexer supplies the addresses in batches.
+    /// Records already in the current layout, including merchants upgraded on first touch, are
+    /// skipped, so batches can overlap or be retried. Balances the reserve floor can't back yet
+    /// are held for `claim_unminted`.
+    ///
+    /// Proposals, wallets, campaigns, lottery rounds and open appeals are not carried over;
+    /// settle them before upgrading. Vote delegations start fresh on the Token contract.
+    pub fn migrate(env: Env, users: Vec<Address>) -> u32 {
+        require_admin(&env);
+        if schema_version(&env) >= SCHEMA_VERSION { panic!("Already migrated"); }
+        if users.len() > MIGRATION_BATCH { panic!("Batch too large"); }
+        let mut rewritten = 0;
+        for user in users.iter() {
+            if migrate_merchant(&env, &user) { rewritten += 1; }
+            if migrate_vault(&env, &user) { rewritten += 1; }
+        }
+        rewritten
+    }
+
+    /// Closes migration once the indexer has swept every address. The old treasury counter
+    /// is minted to the Governance treasury.
+    pub fn finish_migration(env: Env) {
+        require_admin(&env);
+        if let Some(treasury) = env.storage().persistent().get::<_, i128>(&DataKey::Treasury) {
+            env.storage().persistent().remove(&DataKey::Treasury);
+            mint_owed(&env, &deployment(&env).governance, treasury);
+        }
+        store(&env, &DataKey::SchemaVersion, &SCHEMA_VERSION);
+    }
+
+    /// Permissionless. Mints migrated BZR that the reserve floor held back, as far as the
+    /// backing now allows. Returns the amount minted.
+    pub fn claim_unminted(env: Env, user: Address) -> i128 {
+        let key = DataKey::Unminted(user.clone());
+        let owed: i128 = env.storage().persistent().get(&key).expect("Nothing owed");
+        let minted = mint(&env, &user, owed);
+        if minted == owed {
+            env.storage().persistent().remove(&key);
+        } else {
+            store(&env, &key, &(owed - minted));
+        }
+        minted
+    }
+
+    pub fn get_unminted(env: Env, user: Address) -> i128 {
+        env.storage().persistent().get(&DataKey::Unminted(user)).unwrap_or(0)
+    }
+}
+
+// ============================================================
+// 🛠️ INTERNAL HELPERS
+// ============================================================
+
+/// Writes a persistent record and tops up its TTL, so nothing is left on the network minimum.
+fn store<V: IntoVal<Env, Val>>(env: &Env, key: &DataKey, value: &V) {
+    env.storage().persistent().set(key, value);
+    extend_record(env, key);
+}
+
+/// (threshold, extend_to) in ledgers for each record type.
+fn ttl_policy(key: &DataKey) -> (u32, u32) {
+    match key {
+        DataKey::MessageRate(_) => (MESSAGE_WINDOW, RATE_TTL),
+        _ => (RECORD_TTL_THRESHOLD, RECORD_TTL),
+    }
+}
+
+fn extend_record(env: &Env, key: &DataKey) {
+    if !env.storage().persistent().has(key) { return; }
+    let (threshold, extend_to) = ttl_policy(key);
+    env.storage().persistent().extend_ttl(key, threshold, extend_to);
+}
+
+fn schema_version(env: &Env) -> u32 {
+    env.storage().persistent().get(&DataKey::SchemaVersion).unwrap_or(1)
+}
+
+/// The stored record as a raw field map, so layouts can be told apart without a failing decode.
+fn raw_record(env: &Env, key: &DataKey) -> Option<Map<Symbol, Val>> {
+    env.storage().persistent().get(key)
+}
+
+fn migrate_merchant(env: &Env, user: &Address) -> bool {
+    match raw_record(env, &DataKey::Merchant(user.clone())) {
+        Some(raw) => upgrade_merchant(env, user, &raw).is_some(),
+        None => false,
+    }
+}
+
+/// Rewrites a v1 or v2 record in the current layout and mints its old internal BZR balance.
+/// `None` when `raw` is already in the current layout.
+fn upgrade_merchant(env: &Env, user: &Address, raw: &Map<Symbol, Val>) -> Option<Merchant> {
+    let key = DataKey::Merchant(user.clone());
+    // v1 kept plaintext messages on the merchant; inboxes only take ciphertext now, so they are dropped.
+    let (merchant, balance) = if raw.contains_key(Symbol::new(env, "messages")) {
+        let old: MerchantV1 = env.storage().persistent().get(&key).unwrap();
+        let merchant = Merchant { trust_score: old.trust_score, bond_staked: old.bond_staked, badges: old.badges, is_disputed: old.is_disputed, nickname: old.nickname };
+        (merchant, old.bzr_balance)
+    } else if raw.contains_key(Symbol::new(env, "bzr_balance")) {
+        let old: MerchantV2 = env.storage().persistent().get(&key).unwrap();
+        let merchant = Merchant { trust_score: old.trust_score, bond_staked: old.bond_staked, badges: old.badges, is_disputed: old.is_disputed, nickname: old.nickname };
+        (merchant, old.bzr_balance)
+    } else {
+        return None;
+    };
+    save_merchant(env, user, &merchant);
+    mint_owed(env, user, balance);
+    // Juror stakes were debited from the old internal balance; this contract holds them now.
+    if let Some(juror) = env.storage().persistent().get::<_, Juror>(&DataKey::Juror(user.clone())) {
+        mint_owed(env, &env.current_contract_address(), juror.stake);
+    }
+    Some(merchant)
+}
+
+/// Hands the user's vault and Security Circle to the Vault contract and clears them here.
+fn migrate_vault(env: &Env, user: &Address) -> bool {
+    let vault_key = DataKey::Vault(user.clone());
+    let witnesses_key = DataKey::Witnesses(user.clone());
+    let vault_contract = VaultClient::new(env, &deployment(env).vault);
+    let mut moved = false;
+
+    if let Some(raw) = raw_record(env, &vault_key) {
+        let vault = if raw.contains_key(Symbol::new(env, "is_locked")) {
+            let old: LegacyVaultV1 = env.storage().persistent().get(&vault_key).unwrap();
+            // Without an heir the vault could never be claimed
+            old.heir.map(|heir| LegacyVault { heir, last_heartbeat: old.last_heartbeat, is_frozen: old.is_frozen })
+        } else {
+            env.storage().persistent().get::<_, LegacyVault>(&vault_key)
+        };
+        if let Some(vault) = vault { vault_contract.import_vault(user, &vault); }
+        env.storage().persistent().remove(&vault_key);
+        moved = true;
+    }
+    if let Some(witnesses) = env.storage().persistent().get::<_, Vec<Address>>(&witnesses_key) {
+        vault_contract.import_witnesses(user, &witnesses);
+        env.storage().persistent().remove(&witnesses_key);
+        moved = true;
+    }
+    moved
+}
+
+fn unbond(env: &Env, user: &Address, mut merchant: Merchant) {
+    merchant.bond_staked = false;
+    merchant.trust_score = merchant.trust_score.saturating_sub(BOND_POINTS);
+    save_merchant(env, user, &merchant);
+}
+
+fn save_merchant(env: &Env, user: &Address, merchant: &Merchant) {
+    let key = DataKey::Merchant(user.clone());
+    let before = stored_score(env, &key);
+    store(env, &key, merchant);
+    if before != merchant.trust_score { checkpoint_trust(env, user, before, merchant.trust_score); }
+}
+
+/// Score on the stored record, whatever its layout; every layout has `trust_score`.
+fn stored_score(env: &Env, key: &DataKey) -> u32 {
+    raw_record(env, key)
+        .and_then(|raw| raw.get(Symbol::new(env, "trust_score")))
+        .map(|score| u32::try_from_val(env, &score).unwrap())
+        .unwrap_or(0)
+}
+
+fn trust_checkpoint_count(env: &Env, user: &Address) -> u32 {
+    env.storage().persistent().get(&DataKey::TrustCheckpointCount(user.clone())).unwrap_or(0)
+}
+
+fn trust_checkpoint(env: &Env, user: &Address, index: u32) -> TrustCheckpoint {
+    env.storage().persistent().get(&DataKey::TrustCheckpoints(user.clone(), index)).unwrap()
+}
+
+/// Records a score change for the current ledger, folding repeat changes within one ledger.
+/// A merchant's first checkpoint is preceded by one at ledger 0 holding the score from before.
+fn checkpoint_trust(env: &Env, user: &Address, before: u32, score: u32) {
+    let ledger = env.ledger().sequence();
+    let mut count = trust_checkpoint_count(env, user);
+    if count == 0 {
+        write_trust_checkpoint(env, user, 0, TrustCheckpoint { ledger: 0, score: before });
+        count = 1;
+    }
+    let last = trust_checkpoint(env, user, count - 1);
+    let index = if last.ledger == ledger { count - 1 } else { count };
+    write_trust_checkpoint(env, user, index, TrustCheckpoint { ledger, score });
+    let count_key = DataKey::TrustCheckpointCount(user.clone());
+    store(env, &count_key, &(index + 1).max(count));
+}
+
+fn write_trust_checkpoint(env: &Env, user: &Address, index: u32, checkpoint: TrustCheckpoint) {
+    let key = DataKey::TrustCheckpoints(user.clone(), index);
+    store(env, &key, &checkpoint);
+}
+
+/// Binary search for the last checkpoint at or before `ledger`. Merchants without checkpoints
+/// have held their stored score since before checkpoints were kept.
+fn trust_at(env: &Env, user: &Address, ledger: u32) -> u32 {
+    let count = trust_checkpoint_count(env, user);
+    if count == 0 { return stored_score(env, &DataKey::Merchant(user.clone())); }
+    let last = trust_checkpoint(env, user, count - 1);
+    if last.ledger <= ledger { return last.score; }
+
+    let (mut low, mut high) = (0u32, count - 1);
+    while low < high {
+        let mid = (low + high) / 2;
+        if trust_checkpoint(env, user, mid).ledger > ledger { high = mid; } else { low = mid + 1; }
+    }
+    if low == 0 { 0 } else { trust_checkpoint(env, user, low - 1).score }
+}
+
+/// The user's record in the current layout. Records the `migrate` sweep hasn't reached yet are
+/// upgraded on first touch, so those users are never locked out mid-migration.
+fn stored_merchant(env: &Env, user: &Address) -> Option<Merchant> {
+    let raw = raw_record(env, &DataKey::Merchant(user.clone()))?;
+    upgrade_merchant(env, user, &raw).or_else(|| Some(Merchant::try_from_val(env, &raw.to_val()).unwrap()))
+}
+
+fn load_merchant(env: &Env, user: &Address) -> Merchant {
+    stored_merchant(env, user).unwrap_or(Merchant {
+        trust_score: 0, bond_staked: false, badges: Vec::new(env),
+        is_disputed: false, nickname: Symbol::new(env, "User")
+    })
+}
+
+fn require_admin(env: &Env) -> Address {
+    let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+    admin.require_auth();
+    admin
+}
+
+fn require_live(env: &Env) {
+    if env.storage().instance().get(&DataKey::Maintenance).unwrap_or(false) { panic!("Maintenance mode active"); }
+}
+
+fn deployment(env: &Env) -> Deployment {
+    env.storage().instance().get(&DataKey::Deployment).unwrap()
+}
+
+fn bzr(env: &Env) -> token::Client<'_> {
+    token::Client::new(env, &deployment(env).token)
+}
+
+/// Moves `amount` BZR from `from` to the Governance treasury.
+fn pay_treasury(env: &Env, from: &Address, amount: i128) {
+    if amount == 0 { return; }
+    bzr(env).transfer(from, &deployment(env).governance, &amount);
+}
+
+/// New BZR on this contract's minter rights, cut to what the reserve floor still allows so a
+/// reward never reverts the action that earned it. Returns the amount actually minted.
+fn mint(env: &Env, to: &Address, amount: i128) -> i128 {
+    if amount <= 0 { return 0; }
+    let token = BzrClient::new(env, &deployment(env).token);
+    let amount = amount.min(token.mintable());
+    if amount > 0 { token.mint(&env.current_contract_address(), to, &amount); }
+    amount
+}
+
+/// Mints BZR the user is owed; whatever the reserve floor holds back is kept for `claim_unminted`.
+fn mint_owed(env: &Env, to: &Address, amount: i128) {
+    let short = amount - mint(env, to, amount);
+    if short > 0 {
+        let key = DataKey::Unminted(to.clone());
+        let owed: i128 = env.storage().persistent().get(&key).unwrap_or(0);
+        store(env, &key, &(owed + short));
+    }
+}
+
+/// Subscription perks live on the Market contract.
+fn perks_of(env: &Env, user: &Address) -> TierConfig {
+    MarketClient::new(env, &deployment(env).market).get_perks(user)
+}
+
+fn mint_badge(env: &Env, user: &Address, badge: Symbol, mut info: BadgeInfo) {
+    if info.max_supply > 0 && info.minted >= info.max_supply { panic!("Badge sold out"); }
+    let mut merchant = load_merchant(env, user);
+    if merchant.badges.contains(badge.clone()) { panic!("Badge already owned"); }
+    merchant.badges.push_back(badge.clone());
+    save_merchant(env, user, &merchant);
+
+    info.minted += 1;
+    store(env, &DataKey::Badge(badge), &info);
+}
+
+fn has_credential(env: &Env, holder: &Address, course: Symbol) -> bool {
+    env.storage().persistent().get::<_, Credential>(&DataKey::Credential(holder.clone(), course))
+        .map(|c| !c.revoked)
+        .unwrap_or(false)
+}
+
+fn meets_gate(env: &Env, feature: Symbol, user: &Address) -> bool {
+    match env.storage().persistent().get::<_, Symbol>(&DataKey::CredentialGate(feature)) {
+        Some(course) => has_credential(env, user, course),
+        None => true,
+    }
+}
+
+/// Panics unless `user` holds the credential configured for `feature`, if any.
+fn check_gate(env: &Env, feature: Symbol, user: &Address) {
+    if !meets_gate(env, feature, user) { panic!("Missing required credential"); }
+}
+
+fn referral_config(env: &Env) -> ReferralConfig {
+    env.storage().persistent().get(&DataKey::ReferralConfig).unwrap_or(ReferralConfig {
+        level_rewards: Vec::from_array(env, [REFERRAL_L1_REWARD, REFERRAL_L2_REWARD]),
+        cap_per_epoch: REFERRAL_EPOCH_CAP,
+        epoch_length: REFERRAL_EPOCH,
+    })
+}
+
+fn referral_stats(env: &Env, user: &Address) -> ReferralStats {
+    let mut stats = env.storage().persistent().get(&DataKey::ReferralStats(user.clone()))
+        .unwrap_or(ReferralStats { referrer: None, referrals: 0, pending: 0, earned: 0 });
+    stats.referrer = env.storage().persistent().get::<_, Referral>(&DataKey::Referral(user.clone())).map(|r| r.referrer);
+    stats
+}
+
+/// Records the referrer exactly once, rejecting self-referrals and cycles in the referral graph.
+fn record_referral(env: &Env, user: &Address, referrer: Address) {
+    if &referrer == user { panic!("Self-referral not allowed"); }
+    let key = DataKey::Referral(user.clone());
+    if env.storage().persistent().has(&key) { panic!("Referrer already set"); }
+
+    // A cycle needs the user above the referrer, so someone who has already referred others can't be
+    // referred themselves. Roots stay roots, and the graph stays a forest without walking it.
+    if referral_stats(env, user).referrals > 0 { panic!("Referral cycle"); }
+
+    store(env, &key, &Referral { referrer: referrer.clone(), settled: false });
+    let mut stats = referral_stats(env, &referrer);
+    stats.referrals += 1;
+    stats.pending += 1;
+    store(env, &DataKey::ReferralStats(referrer), &stats);
+}
+
+fn lowercase(env: &Env, symbol: &Symbol) -> Symbol {
+    let raw = SymbolStr::try_from_val(env, &symbol.to_symbol_val()).unwrap();
+    let bytes: &[u8] = raw.as_ref();
+    let mut lower = [0u8; 32];
+    for (i, c) in bytes.iter().enumerate() { lower[i] = c.to_ascii_lowercase(); }
+    Symbol::new(env, core::str::from_utf8(&lower[..bytes.len()]).unwrap())
+}
+
+/// Validates `nickname` and returns its lowercased form, used as the registry key.
+fn nickname_key(env: &Env, nickname: &Symbol) -> Symbol {
+    let raw = SymbolStr::try_from_val(env, &nickname.to_symbol_val()).unwrap();
+    let bytes: &[u8] = raw.as_ref();
+    if bytes.len() < NICKNAME_MIN_LEN || bytes.len() > NICKNAME_MAX_LEN { panic!("Invalid nickname length"); }
+    if !bytes[0].is_ascii_alphabetic() { panic!("Nickname must start with a letter"); }
+    if bytes.iter().any(|c| !c.is_ascii_alphanumeric() && *c != b'_') { panic!("Invalid nickname character"); }
+
+    let key = lowercase(env, nickname);
+    // Placeholder names handed out to new profiles can never be registered.
+    if key == Symbol::new(env, "user") || key == Symbol::new(env, "newuser") { panic!("Nickname reserved"); }
+    key
+}
+
+/// Starts the cooldown on the user's current nickname, if they registered one.
+fn release_nickname(env: &Env, user: &Address) {
+    let key = DataKey::Nickname(lowercase(env, &load_merchant(env, user).nickname));
+    if let Some(mut record) = env.storage().persistent().get::<_, NicknameRecord>(&key) {
+        if &record.owner == user && record.release_at == 0 {
+            record.release_at = env.ledger().timestamp() + NICKNAME_COOLDOWN;
+            store(env, &key, &record);
+        }
+    }
+}
+
+fn inbox_of(env: &Env, user: &Address) -> Inbox {
+    env.storage().persistent().get(&DataKey::Inbox(user.clone())).unwrap_or(Inbox { head: 0, next: 0, unread: 0 })
+}
+
+/// The arbiter a disputed or recently exiled merchant is confined to, if any.
+fn confined_to(env: &Env, user: &Address) -> Option<Address> {
+    let dispute: Dispute = env.storage().persistent().get(&DataKey::Dispute(user.clone()))?;
+    let exiled = dispute.upheld && env.ledger().timestamp() < dispute.ruled_at + EXILE_PERIOD;
+    if load_merchant(env, user).is_disputed || exiled { Some(dispute.arbiter) } else { None }
+}
+
+fn check_sender(env: &Env, sender: &Address, recipient: &Address) {
+    if let Some(arbiter) = confined_to(env, sender) {
+        // Confined merchants can always reach their arbiter, whatever their trust
+        if *recipient != arbiter { panic!("Only the arbiter can be messaged"); }
+        return;
+    }
+    let merchant = load_merchant(env, sender);
+    if !merchant.bond_staked { panic!("Sender not bonded"); }
+    if merchant.trust_score < MESSAGE_MIN_TRUST { panic!("Trust score too low"); }
+    if env.storage().persistent().has(&DataKey::ContactsOnly(recipient.clone()))
+        && !env.storage().persistent().has(&DataKey::Vouch(recipient.clone(), sender.clone()))
+    {
+        panic!("Recipient accepts vouched contacts only");
+    }
+}
+
+fn rate_window(env: &Env, user: &Address) -> RateWindow {
+    let window = env.ledger().sequence() / MESSAGE_WINDOW;
+    match env.storage().persistent().get::<_, RateWindow>(&DataKey::MessageRate(user.clone())) {
+        Some(rate) if rate.window == window => rate,
+        _ => RateWindow { window, sent: 0 },
+    }
+}
+
+fn consume_quota(env: &Env, user: &Address) {
+    let mut rate = rate_window(env, user);
+    if rate.sent >= perks_of(env, user).message_quota { panic!("Message rate limit reached"); }
+    rate.sent += 1;
+    store(env, &DataKey::MessageRate(user.clone()), &rate);
+}
+
+/// Governance parameter with a compiled-in fallback.
+fn param(env: &Env, name: Symbol, default: i128) -> i128 {
+    GovernanceClient::new(env, &deployment(env).governance).get_param(&name).unwrap_or(default)
+}
+
+fn role_members(env: &Env, role: Role) -> Vec<Address> {
+    env.storage().persistent().get(&DataKey::RoleMembers(role)).unwrap_or(Vec::new(env))
+}
+
+fn has_role(env: &Env, role: Role, who: &Address) -> bool {
+    match role {
+        Role::Admin => env.storage().instance().get::<_, Address>(&DataKey::Admin).as_ref() == Some(who),
+        _ => role_members(env, role).contains(who.clone()),
+    }
+}
+
+/// The admin seat is single-holder, so granting it hands the seat over.
+fn grant_role(env: &Env, role: Role, who: Address) {
+    if role == Role::Admin {
+        env.storage().instance().set(&DataKey::Admin, &who);
+        return;
+    }
+    let mut members = role_members(env, role);
+    if !members.contains(who.clone()) {
+        members.push_back(who);
+        store(env, &DataKey::RoleMembers(role), &members);
+    }
+}
+
+fn revoke_role(env: &Env, role: Role, who: Address) {
+    if role == Role::Admin { panic!("Admin seat can only be handed over"); }
+    let mut members = role_members(env, role);
+    if let Some(i) = members.first_index_of(who) {
+        members.remove(i);
+        store(env, &DataKey::RoleMembers(role), &members);
+    }
+}
+
+/// Draws an arbiter from the Arbiter role, falling back to the admin when none is eligible.
+fn pick_arbiter(env: &Env, accuser: &Address, target: &Address) -> Address {
+    let mut eligible: Vec<Address> = Vec::new(env);
+    for arbiter in role_members(env, Role::Arbiter).iter() {
+        if &arbiter != accuser && &arbiter != target { eligible.push_back(arbiter); }
+    }
+    if eligible.is_empty() {
+        return env.storage().instance().get(&DataKey::Admin).unwrap();
+    }
+    let pick = env.prng().gen_range::<u64>(0..eligible.len() as u64) as u32;
+    eligible.get(pick).unwrap()
+}
+
+fn set_disputed(env: &Env, user: &Address, disputed: bool) {
+    let mut merchant = load_merchant(env, user);
+    merchant.is_disputed = disputed;
+    save_merchant(env, user, &merchant);
+}
+
+fn appeal_config(env: &Env) -> AppealConfig {
+    env.storage().persistent().get(&DataKey::AppealConfig).unwrap_or(AppealConfig {
+        deposit: APPEAL_DEPOSIT,
+        window: APPEAL_WINDOW,
+        jury_size: JURY_SIZE,
+        min_trust: JUROR_MIN_TRUST,
+        commit_period: JURY_COMMIT_PERIOD,
+        reveal_period: JURY_REVEAL_PERIOD,
+        juror_penalty: JUROR_PENALTY,
+    })
+}
+
+fn is_settled(env: &Env, dispute: &Dispute) -> bool {
+    match dispute.status {
+        DisputeStatus::Final => true,
+        DisputeStatus::Ruled => env.ledger().timestamp() > dispute.ruled_at + appeal_config(env).window,
+        _ => false,
+    }
+}
+
+/// Brings the target's trust score in line with `upheld`, undoing any earlier penalty first.
+fn apply_ruling(env: &Env, dispute: &mut Dispute, upheld: bool) {
+    let mut merchant = load_merchant(env, &dispute.target);
+    merchant.trust_score += dispute.penalty;
+    dispute.penalty = 0;
+    if upheld {
+        dispute.penalty = if merchant.trust_score < DISPUTE_PENALTY { merchant.trust_score } else { DISPUTE_PENALTY };
+        merchant.trust_score -= dispute.penalty;
+    }
+    dispute.upheld = upheld;
+    merchant.is_disputed = false;
+    save_merchant(env, &dispute.target, &merchant);
+}
+
+/// Binding the juror and the appeal stops one juror from copying another's commitment.
+fn vote_commitment(env: &Env, juror: &Address, appeal_id: u32, upheld: bool, salt: &BytesN<32>) -> BytesN<32> {
+    let mut preimage = Bytes::new(env);
+    preimage.push_back(upheld as u8);
+    preimage.extend_from_array(&salt.to_array());
+    preimage.append(&juror.clone().to_xdr(env));
+    preimage.extend_from_array(&appeal_id.to_be_bytes());
+    env.crypto().sha256(&preimage).to_bytes()
+}
+
+/// Draws `jury_size` distinct jurors from bonded pool members above the trust floor,
+/// each pick weighted by the juror's locked stake. Parties to the dispute are excluded.
+fn draw_jury(env: &Env, dispute: &Dispute, config: &AppealConfig) -> Vec<Address> {
+    let pool: Vec<Address> = env.storage().persistent().get(&DataKey::JurorPool).unwrap_or(Vec::new(env));
+    let mut candidates: Vec<Address> = Vec::new(env);
+    let mut weights: Vec<u64> = Vec::new(env);
+    for juror in pool.iter() {
+        if juror == dispute.accuser || juror == dispute.target { continue; }
+        let merchant = load_merchant(env, &juror);
+        if !merchant.bond_staked || merchant.trust_score < config.min_trust { continue; }
+        let record: Juror = env.storage().persistent().get(&DataKey::Juror(juror.clone())).expect("Not a juror");
+        if record.stake <= 0 { continue; }
+        candidates.push_back(juror);
+        weights.push_back(u64::try_from(record.stake).expect("Juror stake out of range"));
+    }
+    if candidates.len() < config.jury_size { panic!("Not enough eligible jurors"); }
+
+    let mut jury: Vec<Address> = Vec::new(env);
+    while jury.len() < config.jury_size {
+        let total: u64 = weights.iter().sum();
+        let mut roll: u64 = env.prng().gen_range(0..total);
+        let mut pick: u32 = 0;
+        for (i, weight) in weights.iter().enumerate() {
+            if roll < weight { pick = i as u32; break; }
+            roll -= weight;
+        }
+        let juror = candidates.get(pick).unwrap();
+        let key = DataKey::Juror(juror.clone());
+        let mut record: Juror = env.storage().persistent().get(&key).unwrap();
+        record.active_cases += 1;
+        store(env, &key, &record);
+
+        jury.push_back(juror);
+        candidates.remove(pick);
+        weights.remove(pick);
+    }
+    jury
+}
+mod test;
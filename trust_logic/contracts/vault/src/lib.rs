@@ -0,0 +1,240 @@
+// 🏛️ PROJECT BAZAAR | VAULT
+// Estate planning and emergency access: the Legacy Vault dead-man switch, the Security Circle
+// of witnesses, Medical Emergency unlocks and the Panic Protocol.
+
+#![no_std]
+use bazaar_types::{Deployment, LegacyVault, DAY_IN_LEDGERS, LEDGER_SECONDS, RECORD_TTL, RECORD_TTL_THRESHOLD};
+use soroban_sdk::{contract, contractimpl, contracttype, Address, BytesN, Env, Vec};
+
+// ============================================================
+// 🔧 PROTOCOL CONSTANTS
+// ============================================================
+
+// Storage TTLs, in ledgers. Every write tops the record back up; `bump` lets anyone do the same.
+const DEADMAN_LIMIT: u64 = 15_552_000;  // 180 Days without a heartbeat before the heir can claim
+const VAULT_CLAIM_WINDOW: u32 = 30 * DAY_IN_LEDGERS; // Vaults live this long past their dead-man deadline
+const EMERGENCY_TTL: u32 = 14 * DAY_IN_LEDGERS;      // Emergency and PanicVotes
+const EMERGENCY_TTL_THRESHOLD: u32 = 7 * DAY_IN_LEDGERS;
+
+// ============================================================
+// 📦 DATA STRUCTURES
+// ============================================================
+
+#[contracttype]
+#[derive(Clone)]
+pub struct MedicalEmergency {
+    pub target_user: Address,
+    pub votes_collected: u32,
+    pub is_unlocked: bool,
+}
+
+#[contracttype]
+pub enum DataKey {
+    Admin,
+    Deployment,
+    Vault(Address),
+    Witnesses(Address),
+    Emergency(Address),
+    PanicVotes(Address),
+}
+
+// ============================================================
+// ⚙️ THE CONTRACT
+// ============================================================
+#[contract]
+pub struct VaultContract;
+
+#[contractimpl]
+impl VaultContract {
+
+    pub fn __constructor(env: Env, admin: Address, deployment: Deployment) {
+        env.storage().instance().set(&DataKey::Admin, &admin);
+        env.storage().instance().set(&DataKey::Deployment, &deployment);
+    }
+
+    pub fn get_admin(env: Env) -> Address {
+        env.storage().instance().get(&DataKey::Admin).unwrap()
+    }
+
+    /// Hands the admin seat over, e.g. to the Governance contract.
+    pub fn set_admin(env: Env, new_admin: Address) {
+        require_admin(&env);
+        env.storage().instance().set(&DataKey::Admin, &new_admin);
+    }
+
+    // --- FEATURE 1: LEGACY VAULT ---
+
+    pub fn create_vault(env: Env, user: Address, heir: Address) {
+        user.require_auth();
+        let vault = LegacyVault {
+            heir,
+            last_heartbeat: env.ledger().timestamp(),
+            is_frozen: false,
+        };
+        env.storage().persistent().set(&DataKey::Vault(user.clone()), &vault);
+        extend_vault(&env, &user, &vault);
+    }
+
+    pub fn ping_heartbeat(env: Env, user: Address) {
+        user.require_auth();
+        let mut vault: LegacyVault = env.storage().persistent().get(&DataKey::Vault(user.clone())).expect("Vault not found");
+        if vault.is_frozen {
+            vault.is_frozen = false;
+        }
+        vault.last_heartbeat = env.ledger().timestamp();
+        env.storage().persistent().set(&DataKey::Vault(user.clone()), &vault);
+        extend_vault(&env, &user, &vault);
+    }
+
+    pub fn claim_legacy(env: Env, target_user: Address) {
+        let vault: LegacyVault = env.storage().persistent().get(&DataKey::Vault(target_user.clone())).expect("Vault not found");
+        vault.heir.require_auth();
+
+        let time_elapsed = env.ledger().timestamp() - vault.last_heartbeat;
+
+        if time_elapsed < DEADMAN_LIMIT {
+            panic!("Owner is still alive");
+        }
+    }
+
+    // --- FEATURE 2: SECURITY CIRCLE ---
+
+    pub fn assign_witnesses(env: Env, user: Address, witnesses: Vec<Address>) {
+        user.require_auth();
+        if witnesses.len() > 5 { panic!("Max 5 witnesses"); }
+        let key = DataKey::Witnesses(user);
+        env.storage().persistent().set(&key, &witnesses);
+        extend_record(&env, &key);
+    }
+
+    pub fn declare_emergency(env: Env, target_user: Address) {
+        let key = DataKey::Emergency(target_user.clone());
+        if env.storage().persistent().has(&key) { panic!("Emergency active"); }
+
+        let emergency = MedicalEmergency {
+            target_user,
+            votes_collected: 0,
+            is_unlocked: false,
+        };
+        env.storage().persistent().set(&key, &emergency);
+        extend_record(&env, &key);
+    }
+
+    pub fn witness_vote_medical(env: Env, witness: Address, target_user: Address) {
+        witness.require_auth();
+        let circle: Vec<Address> = env.storage().persistent().get(&DataKey::Witnesses(target_user.clone())).expect("No Circle");
+        if !circle.contains(witness.clone()) { panic!("Not a witness"); }
+
+        let key = DataKey::Emergency(target_user.clone());
+        let mut emergency: MedicalEmergency = env.storage().persistent().get(&key).expect("No emergency");
+        emergency.votes_collected += 1;
+        if emergency.votes_collected >= 3 {
+            emergency.is_unlocked = true;
+        }
+        env.storage().persistent().set(&key, &emergency);
+        extend_record(&env, &key);
+    }
+
+    pub fn panic_button(env: Env, witness: Address, target_user: Address) {
+        witness.require_auth();
+        let circle: Vec<Address> = env.storage().persistent().get(&DataKey::Witnesses(target_user.clone())).expect("No Circle");
+        if !circle.contains(witness.clone()) { panic!("Not a witness"); }
+
+        let key = DataKey::PanicVotes(target_user.clone());
+        let mut votes: u32 = env.storage().persistent().get(&key).unwrap_or(0);
+        votes += 1;
+        env.storage().persistent().set(&key, &votes);
+        extend_record(&env, &key);
+
+        if votes >= 3 {
+            let mut vault: LegacyVault = env.storage().persistent().get(&DataKey::Vault(target_user.clone())).expect("Vault not found");
+            vault.is_frozen = true;
+            let time_warp = DEADMAN_LIMIT - 604_800;
+            vault.last_heartbeat = env.ledger().timestamp() - time_warp;
+            env.storage().persistent().set(&DataKey::Vault(target_user.clone()), &vault);
+            extend_vault(&env, &target_user, &vault);
+        }
+    }
+
+    // --- FEATURE 3: STORAGE UPKEEP ---
+
+    /// Permissionless: tops up the TTL of everything `user` owns, plus the contract itself.
+    /// Heirs and witnesses can call this to keep a dormant vault from being archived.
+    pub fn bump(env: Env, user: Address) {
+        env.storage().instance().extend_ttl(RECORD_TTL_THRESHOLD, RECORD_TTL);
+        if let Some(vault) = env.storage().persistent().get::<_, LegacyVault>(&DataKey::Vault(user.clone())) {
+            extend_vault(&env, &user, &vault);
+        }
+        for key in [
+            DataKey::Witnesses(user.clone()),
+            DataKey::Emergency(user.clone()),
+            DataKey::PanicVotes(user.clone()),
+        ] {
+            extend_record(&env, &key);
+        }
+    }
+
+    // --- FEATURE 4: UPGRADES & MIGRATION ---
+
+    pub fn upgrade(env: Env, new_wasm_hash: BytesN<32>) {
+        require_admin(&env);
+        env.deployer().update_current_contract_wasm(new_wasm_hash);
+    }
+
+    /// Called by the Trust contract's `migrate` to move vaults out of pre-split storage.
+    /// A vault the user already created here takes precedence.
+    pub fn import_vault(env: Env, user: Address, vault: LegacyVault) {
+        deployment(&env).trust.require_auth();
+        let key = DataKey::Vault(user.clone());
+        if env.storage().persistent().has(&key) { return; }
+        env.storage().persistent().set(&key, &vault);
+        extend_vault(&env, &user, &vault);
+    }
+
+    pub fn import_witnesses(env: Env, user: Address, witnesses: Vec<Address>) {
+        deployment(&env).trust.require_auth();
+        let key = DataKey::Witnesses(user);
+        if env.storage().persistent().has(&key) { return; }
+        env.storage().persistent().set(&key, &witnesses);
+        extend_record(&env, &key);
+    }
+}
+
+// ============================================================
+// 🛠️ INTERNAL HELPERS
+// ============================================================
+
+fn require_admin(env: &Env) -> Address {
+    let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+    admin.require_auth();
+    admin
+}
+
+fn deployment(env: &Env) -> Deployment {
+    env.storage().instance().get(&DataKey::Deployment).unwrap()
+}
+
+/// (threshold, extend_to) in ledgers for each record type.
+fn ttl_policy(key: &DataKey) -> (u32, u32) {
+    match key {
+        DataKey::Emergency(_) | DataKey::PanicVotes(_) => (EMERGENCY_TTL_THRESHOLD, EMERGENCY_TTL),
+        _ => (RECORD_TTL_THRESHOLD, RECORD_TTL),
+    }
+}
+
+fn extend_record(env: &Env, key: &DataKey) {
+    if !env.storage().persistent().has(key) { return; }
+    let (threshold, extend_to) = ttl_policy(key);
+    env.storage().persistent().extend_ttl(key, threshold, extend_to);
+}
+
+/// Keeps a vault alive until its dead-man deadline plus the claim window, measured from the last heartbeat.
+/// Always tops up (threshold == extend_to); the network's max TTL may still clamp it, which `bump` covers.
+fn extend_vault(env: &Env, user: &Address, vault: &LegacyVault) {
+    let deadline = vault.last_heartbeat + DEADMAN_LIMIT;
+    let remaining = deadline.saturating_sub(env.ledger().timestamp()) / LEDGER_SECONDS;
+    let extend_to = (remaining as u32).saturating_add(VAULT_CLAIM_WINDOW);
+    env.storage().persistent().extend_ttl(&DataKey::Vault(user.clone()), extend_to, extend_to);
+}
+
+mod test;
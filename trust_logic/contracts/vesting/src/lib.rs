@@ -0,0 +1,216 @@
+// 🏛️ PROJECT BAZAAR | VESTING
+// Founder and team BZR locked under public cliff + linear schedules, so anyone can check on-chain
+// how much of an allocation has vested. Revocable grants can be cut back by the DAO: what has
+// vested stays with the beneficiary, the rest returns to the Governance treasury.
+// Genesis allocations are locked here before the token's admin seat moves to Governance, so
+// after launch the founder can neither spend unvested BZR nor mint more.
+
+#![no_std]
+use bazaar_types::{Deployment, RECORD_TTL, RECORD_TTL_THRESHOLD};
+use soroban_sdk::{contract, contractimpl, contracttype, token, Address, BytesN, Env, IntoVal, Val, Vec};
+
+// ============================================================
+// 📦 DATA STRUCTURES
+// ============================================================
+
+/// Nothing vests before `start + cliff`; from then the grant has vested linearly since `start`,
+/// in full at `start + duration`. A pure cliff grant sets `cliff == duration`.
+#[contracttype]
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Schedule {
+    pub start: u64,
+    /// Seconds after `start`.
+    pub cliff: u64,
+    pub duration: u64,
+}
+
+#[contracttype]
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Grant {
+    pub beneficiary: Address,
+    pub amount: i128,
+    pub schedule: Schedule,
+    pub revocable: bool,
+    pub claimed: i128,
+    /// When set, `amount` has been cut back to what had vested at this time.
+    pub revoked_at: Option<u64>,
+}
+
+#[contracttype]
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct VestingStatus {
+    pub vested: i128,
+    pub unvested: i128,
+    pub claimed: i128,
+    pub claimable: i128,
+}
+
+#[contracttype]
+pub enum DataKey {
+    Admin,
+    Deployment,
+    GrantCount,
+    Grant(u32),
+    GrantsOf(Address),
+}
+
+// ============================================================
+// ⚙️ THE CONTRACT
+// ============================================================
+#[contract]
+pub struct VestingContract;
+
+#[contractimpl]
+impl VestingContract {
+
+    pub fn __constructor(env: Env, admin: Address, deployment: Deployment) {
+        env.storage().instance().set(&DataKey::Admin, &admin);
+        env.storage().instance().set(&DataKey::Deployment, &deployment);
+    }
+
+    pub fn get_admin(env: Env) -> Address {
+        env.storage().instance().get(&DataKey::Admin).unwrap()
+    }
+
+    /// Hands the admin seat, and with it the power to revoke, to the Governance contract.
+    pub fn set_admin(env: Env, new_admin: Address) {
+        require_admin(&env);
+        env.storage().instance().set(&DataKey::Admin, &new_admin);
+    }
+
+    pub fn upgrade(env: Env, new_wasm_hash: BytesN<32>) {
+        require_admin(&env);
+        env.deployer().update_current_contract_wasm(new_wasm_hash);
+    }
+
+    // --- FEATURE 1: GRANTS ---
+
+    /// Locks `amount` of the funder's BZR under a new schedule for `beneficiary`.
+    pub fn create_grant(env: Env, funder: Address, beneficiary: Address, amount: i128, schedule: Schedule, revocable: bool) -> u32 {
+        funder.require_auth();
+        if amount <= 0 { panic!("Invalid amount"); }
+        if schedule.duration == 0 || schedule.cliff > schedule.duration { panic!("Invalid schedule"); }
+        bzr(&env).transfer(&funder, env.current_contract_address(), &amount);
+
+        let id: u32 = env.storage().persistent().get(&DataKey::GrantCount).unwrap_or(0) + 1;
+        let grant = Grant { beneficiary: beneficiary.clone(), amount, schedule, revocable, claimed: 0, revoked_at: None };
+        save_grant(&env, id, &grant);
+        store(&env, &DataKey::GrantCount, &id);
+
+        let list_key = DataKey::GrantsOf(beneficiary);
+        let mut grants: Vec<u32> = env.storage().persistent().get(&list_key).unwrap_or(Vec::new(&env));
+        grants.push_back(id);
+        store(&env, &list_key, &grants);
+        id
+    }
+
+    /// Pays the beneficiary everything vested and not yet claimed.
+    pub fn claim_vested(env: Env, grant_id: u32) -> i128 {
+        let mut grant = Self::get_grant(env.clone(), grant_id);
+        grant.beneficiary.require_auth();
+        let claimable = vested(&env, &grant) - grant.claimed;
+        if claimable <= 0 { panic!("Nothing vested"); }
+
+        grant.claimed += claimable;
+        save_grant(&env, grant_id, &grant);
+        bzr(&env).transfer(&env.current_contract_address(), &grant.beneficiary, &claimable);
+        claimable
+    }
+
+    /// Admin only, i.e. the DAO through a `RevokeGrant` proposal. Freezes the grant at what has
+    /// vested so far and returns the unvested BZR to the treasury.
+    pub fn revoke_grant(env: Env, grant_id: u32) {
+        require_admin(&env);
+        let mut grant = Self::get_grant(env.clone(), grant_id);
+        if !grant.revocable { panic!("Grant not revocable"); }
+        if grant.revoked_at.is_some() { panic!("Grant already revoked"); }
+
+        let vested = vested(&env, &grant);
+        let unvested = grant.amount - vested;
+        grant.amount = vested;
+        grant.revoked_at = Some(env.ledger().timestamp());
+        save_grant(&env, grant_id, &grant);
+        if unvested > 0 {
+            bzr(&env).transfer(&env.current_contract_address(), &deployment(&env).governance, &unvested);
+        }
+    }
+
+    pub fn get_grant(env: Env, grant_id: u32) -> Grant {
+        env.storage().persistent().get(&DataKey::Grant(grant_id)).expect("Grant not found")
+    }
+
+    pub fn get_grants(env: Env, beneficiary: Address) -> Vec<u32> {
+        env.storage().persistent().get(&DataKey::GrantsOf(beneficiary)).unwrap_or(Vec::new(&env))
+    }
+
+    /// Vested vs unvested BZR for a grant right now. After a revocation, unvested is zero.
+    pub fn get_vesting(env: Env, grant_id: u32) -> VestingStatus {
+        let grant = Self::get_grant(env.clone(), grant_id);
+        let vested = vested(&env, &grant);
+        VestingStatus {
+            vested,
+            unvested: grant.amount - vested,
+            claimed: grant.claimed,
+            claimable: vested - grant.claimed,
+        }
+    }
+
+    // --- FEATURE 2: STORAGE UPKEEP ---
+
+    /// Permissionless: tops up the TTL of `beneficiary`'s grants, plus the contract itself.
+    pub fn bump(env: Env, beneficiary: Address) {
+        env.storage().instance().extend_ttl(RECORD_TTL_THRESHOLD, RECORD_TTL);
+        for id in Self::get_grants(env.clone(), beneficiary.clone()).iter() {
+            extend_record(&env, &DataKey::Grant(id));
+        }
+        for key in [DataKey::GrantsOf(beneficiary), DataKey::GrantCount] {
+            extend_record(&env, &key);
+        }
+    }
+}
+
+// ============================================================
+// 🛠️ INTERNAL HELPERS
+// ============================================================
+
+fn require_admin(env: &Env) -> Address {
+    let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+    admin.require_auth();
+    admin
+}
+
+fn deployment(env: &Env) -> Deployment {
+    env.storage().instance().get(&DataKey::Deployment).unwrap()
+}
+
+fn bzr(env: &Env) -> token::Client<'_> {
+    token::Client::new(env, &deployment(env).token)
+}
+
+/// BZR vested under `grant` at the current ledger time. Revoked grants have fully vested their cut-back amount.
+fn vested(env: &Env, grant: &Grant) -> i128 {
+    if grant.revoked_at.is_some() { return grant.amount; }
+    let schedule = &grant.schedule;
+    let now = env.ledger().timestamp();
+    if now < schedule.start + schedule.cliff { return 0; }
+    let elapsed = now - schedule.start;
+    if elapsed >= schedule.duration { return grant.amount; }
+    grant.amount * elapsed as i128 / schedule.duration as i128
+}
+
+fn save_grant(env: &Env, id: u32, grant: &Grant) {
+    store(env, &DataKey::Grant(id), grant);
+}
+
+/// Writes a persistent record and tops up its TTL.
+fn store<V: IntoVal<Env, Val>>(env: &Env, key: &DataKey, value: &V) {
+    env.storage().persistent().set(key, value);
+    extend_record(env, key);
+}
+
+fn extend_record(env: &Env, key: &DataKey) {
+    if !env.storage().persistent().has(key) { return; }
+    env.storage().persistent().extend_ttl(key, RECORD_TTL_THRESHOLD, RECORD_TTL);
+}
+
+mod test;
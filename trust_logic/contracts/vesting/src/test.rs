@@ -0,0 +1,133 @@
+#![cfg(test)]
+
+use super::*;
+use bazaar_token::{BzrToken, BzrTokenClient};
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    Address, Env,
+};
+
+const START: u64 = 1_773_273_600; // Genesis mint, 2026-03-12
+const YEAR: u64 = 31_536_000;
+
+struct Bazaar {
+    vesting: VestingContractClient<'static>,
+    token: BzrTokenClient<'static>,
+    treasury: Address,
+    founder: Address,
+}
+
+fn setup(env: &Env) -> Bazaar {
+    env.ledger().with_mut(|li| { li.timestamp = START; });
+    let admin = Address::generate(env);
+    let token = BzrTokenClient::new(env, &env.register(BzrToken, (&admin,)));
+    let treasury = Address::generate(env);
+    let deployment = Deployment {
+        trust: Address::generate(env),
+        vault: Address::generate(env),
+        token: token.address.clone(),
+        market: Address::generate(env),
+        governance: treasury.clone(),
+    };
+    let vesting = VestingContractClient::new(env, &env.register(VestingContract, (&admin, &deployment)));
+    let founder = Address::generate(env);
+    token.mock_all_auths().mint(&admin, &founder, &3_000);
+    Bazaar { vesting, token, treasury, founder }
+}
+
+fn at(env: &Env, timestamp: u64) {
+    env.ledger().with_mut(|li| { li.timestamp = timestamp; });
+}
+
+/// One-year cliff, four years in total.
+fn founder_grant(bz: &Bazaar, beneficiary: &Address, revocable: bool) -> u32 {
+    let schedule = Schedule { start: START, cliff: YEAR, duration: 4 * YEAR };
+    bz.vesting.create_grant(&bz.founder, beneficiary, &1_200, &schedule, &revocable)
+}
+
+#[test]
+fn test_cliff_then_linear_vesting() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let bz = setup(&env);
+    let team = Address::generate(&env);
+
+    let id = founder_grant(&bz, &team, false);
+    assert_eq!(bz.token.balance(&bz.vesting.address), 1_200);
+    assert_eq!(bz.vesting.get_grants(&team), soroban_sdk::vec![&env, id]);
+
+    at(&env, START + YEAR - 1);
+    assert_eq!(bz.vesting.get_vesting(&id), VestingStatus { vested: 0, unvested: 1_200, claimed: 0, claimable: 0 });
+    assert!(bz.vesting.try_claim_vested(&id).is_err());
+
+    // The cliff releases everything accrued since the start at once
+    at(&env, START + YEAR);
+    assert_eq!(bz.vesting.claim_vested(&id), 300);
+    at(&env, START + 2 * YEAR);
+    assert_eq!(bz.vesting.get_vesting(&id), VestingStatus { vested: 600, unvested: 600, claimed: 300, claimable: 300 });
+
+    at(&env, START + 5 * YEAR);
+    assert_eq!(bz.vesting.claim_vested(&id), 900);
+    assert_eq!(bz.token.balance(&team), 1_200);
+}
+
+#[test]
+fn test_pure_cliff_grant() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let bz = setup(&env);
+    let team = Address::generate(&env);
+    let schedule = Schedule { start: START, cliff: 3 * YEAR, duration: 3 * YEAR };
+
+    let id = bz.vesting.create_grant(&bz.founder, &team, &500, &schedule, &false);
+    at(&env, START + 3 * YEAR - 1);
+    assert_eq!(bz.vesting.get_vesting(&id).vested, 0);
+    at(&env, START + 3 * YEAR);
+    assert_eq!(bz.vesting.get_vesting(&id).vested, 500);
+}
+
+#[test]
+fn test_revoke_returns_unvested_to_treasury() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let bz = setup(&env);
+    let team = Address::generate(&env);
+
+    let id = founder_grant(&bz, &team, true);
+    at(&env, START + 2 * YEAR);
+    bz.vesting.revoke_grant(&id);
+    assert_eq!(bz.token.balance(&bz.treasury), 600);
+
+    // What had vested stays claimable, and nothing more accrues
+    at(&env, START + 4 * YEAR);
+    assert_eq!(bz.vesting.get_vesting(&id), VestingStatus { vested: 600, unvested: 0, claimed: 0, claimable: 600 });
+    assert_eq!(bz.vesting.claim_vested(&id), 600);
+    assert_eq!(bz.vesting.get_grant(&id).revoked_at, Some(START + 2 * YEAR));
+    assert!(bz.vesting.try_revoke_grant(&id).is_err());
+}
+
+#[test]
+#[should_panic(expected = "Grant not revocable")]
+fn test_irrevocable_grant_cannot_be_revoked() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let bz = setup(&env);
+
+    let id = founder_grant(&bz, &Address::generate(&env), false);
+    bz.vesting.revoke_grant(&id);
+}
+
+#[test]
+fn test_only_admin_revokes() {
+    let env = Env::default();
+    let bz = setup(&env);
+
+    let id = bz.vesting.mock_all_auths().create_grant(
+        &bz.founder,
+        &Address::generate(&env),
+        &1_200,
+        &Schedule { start: START, cliff: 0, duration: YEAR },
+        &true,
+    );
+    assert!(bz.vesting.try_revoke_grant(&id).is_err());
+}